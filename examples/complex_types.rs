@@ -51,7 +51,7 @@ impl GameState {
 
     fn get_leaderboard(&self) -> Vec<(&User, i32)> {
         let mut users: Vec<_> = self.users.values().map(|u| (u, u.score)).collect();
-        users.sort_by(|a, b| b.1.cmp(&a.1));
+        users.sort_by_key(|u| std::cmp::Reverse(u.1));
         users
     }
 }
@@ -140,13 +140,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n--- Working with Nested Data ---");
 
     // Create a complex nested structure
-    let app_data = A::new(HashMap::<String, A<Vec<String>>>::new());
-
-    // Initialize some data
-    app_data.update(|data| {
-        data.insert("logs".to_string(), A::new(vec![]));
-        data.insert("messages".to_string(), A::new(vec![]));
-    });
+    let app_data: A<HashMap<String, A<Vec<String>>>> = A::nested_map([
+        ("logs".to_string(), vec![]),
+        ("messages".to_string(), vec![]),
+    ]);
 
     // Add logs from different tasks
     let logs_ref = {
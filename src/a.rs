@@ -1,7 +1,24 @@
 //! Thread-safe wrapper around `Arc<RwLock<T>>` with convenient cloning and access methods.
 
-use parking_lot::{ArcRwLockWriteGuard, RawRwLock, RwLock};
+use crate::guard::Locked;
+use crate::{MappedWriteGuard, ReadGuard, TimedOut, WriteGuard};
+use parking_lot::{
+    ArcRwLockUpgradableReadGuard, RawRwLock, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+use std::future::Future;
+use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "instrumented")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "lock_owner_tracking")]
+use std::panic::Location;
+#[cfg(feature = "stream")]
+use std::sync::OnceLock;
+#[cfg(feature = "lock_owner_tracking")]
+use std::thread::ThreadId;
 
 /// A thread-safe wrapper around `Arc<RwLock<T>>` that provides convenient cloning semantics
 /// and easy access to the inner value.
@@ -65,8 +82,201 @@ use std::sync::Arc;
 ///
 /// assert_eq!(data.get(), "Hello, World!");
 /// ```
+///
+/// ## Trait Objects
+///
+/// Handles with different concrete types can be stored together as
+/// `A<Box<dyn Trait>>`. `A::new(Box::new(value))` coerces to the trait object
+/// wherever the target type is known, and methods called through a guard or
+/// closure dispatch dynamically:
+///
+/// ```rust
+/// use tspawn::A;
+///
+/// trait Plugin: Send + Sync {
+///     fn name(&self) -> &str;
+/// }
+///
+/// struct Metrics;
+/// impl Plugin for Metrics {
+///     fn name(&self) -> &str { "metrics" }
+/// }
+///
+/// struct Auth;
+/// impl Plugin for Auth {
+///     fn name(&self) -> &str { "auth" }
+/// }
+///
+/// let plugins: Vec<A<Box<dyn Plugin>>> = vec![A::new(Box::new(Metrics)), A::new(Box::new(Auth))];
+/// let names: Vec<String> = plugins.iter().map(|p| p.read().name().to_string()).collect();
+/// assert_eq!(names, ["metrics", "auth"]);
+/// ```
 pub struct A<T> {
-    value: Arc<RwLock<T>>,
+    pub(crate) value: Arc<RwLock<T>>,
+    pub(crate) extras: Extras<T>,
+}
+
+/// Per-handle state backing the optional features, shared by all clones.
+///
+/// Without any features enabled this only holds the fairness flag.
+pub(crate) struct Extras<T> {
+    pub(crate) fair: bool,
+    #[cfg(feature = "labeled")]
+    pub(crate) label: Option<Arc<str>>,
+    #[cfg(feature = "instrumented")]
+    pub(crate) count_contention: bool,
+    #[cfg(feature = "instrumented")]
+    write_contention: Arc<AtomicU64>,
+    #[cfg(feature = "lock_owner_tracking")]
+    pub(crate) writer: Arc<parking_lot::Mutex<Option<(ThreadId, &'static Location<'static>)>>>,
+    #[cfg(feature = "stream")]
+    changes: Arc<OnceLock<ChangeSender<T>>>,
+    #[cfg(feature = "observers")]
+    pub(crate) write_hook: Option<WriteHook<T>>,
+    #[cfg(feature = "lock_order")]
+    pub(crate) level: Arc<parking_lot::Mutex<Option<u32>>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Extras<T> {
+    pub(crate) fn new() -> Self {
+        Extras {
+            fair: false,
+            #[cfg(feature = "labeled")]
+            label: None,
+            #[cfg(feature = "instrumented")]
+            count_contention: true,
+            #[cfg(feature = "instrumented")]
+            write_contention: Arc::new(AtomicU64::new(0)),
+            #[cfg(feature = "lock_owner_tracking")]
+            writer: Arc::new(parking_lot::Mutex::new(None)),
+            #[cfg(feature = "stream")]
+            changes: Arc::new(OnceLock::new()),
+            #[cfg(feature = "observers")]
+            write_hook: None,
+            #[cfg(feature = "lock_order")]
+            level: Arc::new(parking_lot::Mutex::new(None)),
+            _marker: PhantomData,
+        }
+    }
+
+    #[cfg(feature = "labeled")]
+    fn run_labeled<R>(&self, f: impl FnOnce() -> R) -> R {
+        use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+
+        let Some(label) = &self.label else {
+            return f();
+        };
+        catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            resume_unwind(Box::new(format!("[{label}] {message}")))
+        })
+    }
+
+    #[cfg(not(feature = "labeled"))]
+    fn run_labeled<R>(&self, f: impl FnOnce() -> R) -> R {
+        f()
+    }
+}
+
+impl<T> Clone for Extras<T> {
+    fn clone(&self) -> Self {
+        Extras {
+            fair: self.fair,
+            #[cfg(feature = "labeled")]
+            label: self.label.clone(),
+            #[cfg(feature = "instrumented")]
+            count_contention: self.count_contention,
+            #[cfg(feature = "instrumented")]
+            write_contention: Arc::clone(&self.write_contention),
+            #[cfg(feature = "lock_owner_tracking")]
+            writer: Arc::clone(&self.writer),
+            #[cfg(feature = "stream")]
+            changes: Arc::clone(&self.changes),
+            #[cfg(feature = "observers")]
+            write_hook: self.write_hook.clone(),
+            #[cfg(feature = "lock_order")]
+            level: Arc::clone(&self.level),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Callback installed by [`A::with_write_hook`].
+#[cfg(feature = "observers")]
+pub(crate) type WriteHook<T> = Arc<dyn Fn(&T) + Send + Sync>;
+
+/// Broadcast channel feeding [`A::changes`], created on first subscription.
+#[cfg(feature = "stream")]
+struct ChangeSender<T> {
+    sender: tokio::sync::broadcast::Sender<T>,
+    clone: fn(&T) -> T,
+    /// Subscribers from [`A::subscribe_where`], each filtering before it sends.
+    /// A subscriber returns `false` once its receiver is gone.
+    filtered: parking_lot::Mutex<Vec<FilteredSender<T>>>,
+}
+
+#[cfg(feature = "stream")]
+type FilteredSender<T> = Box<dyn FnMut(&T) -> bool + Send>;
+
+#[cfg(feature = "stream")]
+impl<T> ChangeSender<T> {
+    fn new() -> Self
+    where
+        T: Clone,
+    {
+        ChangeSender {
+            sender: tokio::sync::broadcast::channel(16).0,
+            clone: T::clone,
+            filtered: parking_lot::Mutex::new(Vec::new()),
+        }
+    }
+}
+
+/// Stream behind [`A::write_stream`].
+#[cfg(feature = "stream")]
+struct WriteStream<T> {
+    handle: A<T>,
+    /// Timer to wait out before the next attempt, set while the lock is taken.
+    retry: Option<std::pin::Pin<Box<tokio::time::Sleep>>>,
+    /// Delay before the next retry, doubled after each failed attempt.
+    backoff: Duration,
+}
+
+#[cfg(feature = "stream")]
+impl<T> WriteStream<T> {
+    const MIN_BACKOFF: Duration = Duration::from_micros(50);
+    const MAX_BACKOFF: Duration = Duration::from_millis(10);
+}
+
+#[cfg(feature = "stream")]
+impl<T> tokio_stream::Stream for WriteStream<T> {
+    type Item = WriteGuard<T>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        loop {
+            if let Some(retry) = self.retry.as_mut() {
+                std::task::ready!(retry.as_mut().poll(cx));
+                self.retry = None;
+            }
+            if let Some(guard) = self.handle.try_write_arc() {
+                self.backoff = Self::MIN_BACKOFF;
+                return std::task::Poll::Ready(Some(guard));
+            }
+            // There is no notification when the lock is released, so sleep and
+            // retry, backing off while the lock stays busy.
+            let delay = self.backoff;
+            self.backoff = (delay * 2).min(Self::MAX_BACKOFF);
+            self.retry = Some(Box::pin(tokio::time::sleep(delay)));
+        }
+    }
 }
 
 impl<T> Clone for A<T> {
@@ -89,10 +299,25 @@ impl<T> Clone for A<T> {
     fn clone(&self) -> Self {
         A {
             value: Arc::clone(&self.value),
+            extras: self.extras.clone(),
         }
     }
 }
 
+impl<T> From<RwLock<T>> for A<T> {
+    /// Wraps an unshared lock, as [`A::from_rwlock`] does.
+    fn from(lock: RwLock<T>) -> Self {
+        Self::from_rwlock(lock)
+    }
+}
+
+impl<T> From<Box<RwLock<T>>> for A<T> {
+    /// Moves a boxed lock into a fresh `Arc`.
+    fn from(lock: Box<RwLock<T>>) -> Self {
+        Self::from_inner(Arc::from(lock))
+    }
+}
+
 impl<T> A<T> {
     /// Creates a new `A<T>` wrapping the given value.
     ///
@@ -105,9 +330,7 @@ impl<T> A<T> {
     /// assert_eq!(data.get(), 42);
     /// ```
     pub fn new(value: T) -> Self {
-        A {
-            value: Arc::new(RwLock::new(value)),
-        }
+        Self::from_inner(Arc::new(RwLock::new(value)))
     }
 
     /// Returns a clone of the inner value.
@@ -128,7 +351,7 @@ impl<T> A<T> {
     where
         T: Clone,
     {
-        self.value.read().clone()
+        self.lock_read().clone()
     }
 
     /// Returns `Some(T)` containing a clone of the inner value.
@@ -148,7 +371,164 @@ impl<T> A<T> {
     where
         T: Clone,
     {
-        self.value.read().clone().into()
+        self.lock_read().clone().into()
+    }
+
+    /// Returns a clone of the inner value wrapped in an `Arc`.
+    ///
+    /// The value is cloned once under a read lock; after that, the returned `Arc`
+    /// can be handed to any number of read-heavy consumers without cloning `T`
+    /// again and without touching the lock. This is the canonical cheap-to-share
+    /// snapshot of the current state.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use tspawn::A;
+    ///
+    /// let config = A::new(String::from("v1"));
+    /// let snapshot = config.read_clone_arc();
+    /// let for_worker = Arc::clone(&snapshot);
+    ///
+    /// config.set(String::from("v2"));
+    /// assert_eq!(*for_worker, "v1");
+    /// ```
+    pub fn read_clone_arc(&self) -> Arc<T>
+    where
+        T: Clone,
+    {
+        Arc::new(self.get())
+    }
+
+    /// Computes an owned value from the inner value under a read lock.
+    ///
+    /// Only what `f` returns is produced, so this is the minimal-clone way to pull
+    /// a field or summary out of a large value where [`A::get`] would clone all of
+    /// it. A borrow cannot outlive the lock, so a function that sometimes borrows
+    /// and sometimes owns should take the `Cow` decision inside `f` and return
+    /// `Cow::Owned`, or hold a guard from [`A::read`] for as long as it borrows.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// struct Session {
+    ///     user: String,
+    ///     history: Vec<String>,
+    /// }
+    ///
+    /// let session = A::new(Session { user: "ada".into(), history: vec!["/".into(); 1000] });
+    /// let user: String = session.map_owned(|s| s.user.clone());
+    /// assert_eq!(user, "ada");
+    /// assert_eq!(session.map_owned(|s| s.history.len()), 1000);
+    /// ```
+    pub fn map_owned<U, F>(&self, f: F) -> U
+    where
+        F: FnOnce(&T) -> U,
+    {
+        self.run_labeled(|| f(&self.lock_read()))
+    }
+
+    /// Runs `f` with the value's bytes under a read lock, without copying them.
+    ///
+    /// Works for any byte-backed value such as `Vec<u8>`, `String` or `Box<[u8]>`,
+    /// for example to write shared state straight into an IO buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let greeting = A::new(String::from("hi"));
+    /// let mut out = Vec::new();
+    /// greeting.with_bytes(|bytes| out.extend_from_slice(bytes));
+    /// assert_eq!(out, b"hi");
+    /// ```
+    pub fn with_bytes<R, F>(&self, f: F) -> R
+    where
+        T: AsRef<[u8]>,
+        F: FnOnce(&[u8]) -> R,
+    {
+        self.run_labeled(|| f(self.lock_read().as_ref()))
+    }
+
+    /// Clones the inner value, converts it with `Into`, and wraps it in a new `A<U>`.
+    ///
+    /// The result is an independent snapshot: it does not share storage with
+    /// `self`, and later changes to either handle are not reflected in the other.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let small = A::new(7u8);
+    /// let wide: A<u32> = small.map_into();
+    /// assert_eq!(wide.get(), 7);
+    /// ```
+    pub fn map_into<U>(&self) -> A<U>
+    where
+        T: Clone,
+        U: From<T>,
+    {
+        A::new(U::from(self.get()))
+    }
+
+    /// Clones the inner value, passes it to an async function, and wraps the result
+    /// in a new `A<U>`.
+    ///
+    /// The value is cloned when this method is called and the lock is released
+    /// immediately, so no lock is held while the returned future is awaited.
+    /// Like [`A::map_into`], the result does not share storage with `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let id = A::new(7u32);
+    /// let label = id.map_inner_async(|id| async move { format!("user-{id}") }).await;
+    /// assert_eq!(label.get(), "user-7");
+    /// # }
+    /// ```
+    pub fn map_inner_async<U, Fut, F>(&self, f: F) -> impl Future<Output = A<U>>
+    where
+        T: Clone,
+        F: FnOnce(T) -> Fut,
+        Fut: Future<Output = U>,
+    {
+        let future = f(self.get());
+        async move { A::new(future.await) }
+    }
+
+    /// Converts the inner value with a fallible function and wraps the result in a
+    /// new `A<U>`.
+    ///
+    /// `f` runs under a read lock. On success the converted value is wrapped in a
+    /// fresh handle that, like [`A::map_into`], does not share storage with `self`;
+    /// on failure the error is returned and nothing is created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let port = A::new(String::from("8080"));
+    /// let parsed = port.try_map_inner(|s| s.parse::<u16>()).unwrap();
+    /// assert_eq!(parsed.get(), 8080);
+    ///
+    /// let bad = A::new(String::from("http"));
+    /// assert!(bad.try_map_inner(|s| s.parse::<u16>()).is_err());
+    /// ```
+    pub fn try_map_inner<U, E, F>(&self, f: F) -> Result<A<U>, E>
+    where
+        F: FnOnce(&T) -> Result<U, E>,
+    {
+        self.run_labeled(|| f(&self.lock_read())).map(A::new)
     }
 
     /// Sets the inner value to the provided value.
@@ -165,95 +545,109 @@ impl<T> A<T> {
     /// assert_eq!(data.get(), 100);
     /// ```
     pub fn set(&self, value: T) {
-        *self.value.write() = value;
+        let mut guard = self.lock_write();
+        *guard = value;
+        self.finish_write(guard);
     }
 
-    /// Updates the inner value using a closure.
+    /// Sets the inner value only if it differs from the current one.
     ///
-    /// This method acquires a write lock and calls the provided closure with
-    /// a mutable reference to the inner value.
+    /// Returns `true` if the value was written. Equal values are skipped entirely,
+    /// so with the `stream` feature subscribers to `A::changes` are not woken
+    /// by no-op writes.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use tspawn::A;
     ///
-    /// let data = A::new(vec![1, 2, 3]);
-    /// data.update(|v| v.push(4));
-    /// assert_eq!(data.get(), vec![1, 2, 3, 4]);
+    /// let status = A::new("idle");
+    /// assert!(!status.set_notify("idle"));
+    /// assert!(status.set_notify("busy"));
+    /// assert_eq!(status.get(), "busy");
     /// ```
-    pub fn update<F>(&self, f: F)
+    pub fn set_notify(&self, value: T) -> bool
     where
-        F: FnOnce(&mut T),
+        T: PartialEq,
     {
-        let mut guard = self.value.write();
-        f(&mut guard);
+        let mut guard = self.lock_write();
+        if *guard == value {
+            self.release_write(guard);
+            return false;
+        }
+        *guard = value;
+        self.finish_write(guard);
+        true
     }
 
-    /// Returns a read guard for the inner value.
+    /// Returns a clone of the inner value, or `None` if the lock is held for writing.
     ///
-    /// This allows for more complex read operations without cloning the data.
-    /// The guard will automatically release the lock when dropped.
+    /// This never blocks and only clones when the read lock is acquired, making it
+    /// suitable for best-effort snapshots in logging or metrics paths.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use tspawn::A;
     ///
-    /// let data = A::new(String::from("Hello"));
-    /// let guard = data.read();
-    /// assert_eq!(&*guard, "Hello");
-    /// // Lock is automatically released when guard is dropped
+    /// let data = A::new(42);
+    /// assert_eq!(data.try_clone_value(), Some(42));
+    ///
+    /// let guard = data.write();
+    /// assert_eq!(data.try_clone_value(), None);
+    /// # drop(guard);
     /// ```
-    pub fn read(&self) -> parking_lot::ArcRwLockReadGuard<RawRwLock, T> {
-        self.value.read_arc()
+    pub fn try_clone_value(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.try_lock_read().map(|guard| guard.clone())
     }
 
-    /// Returns a write guard for the inner value.
+    /// Returns a clone of the inner value.
     ///
-    /// This allows for more complex write operations. The guard will
-    /// automatically release the lock when dropped.
+    /// This is an alias of [`A::get`] for users coming from `std::sync::atomic`
+    /// or `arc-swap`, where the equivalent operation is called `load`.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use tspawn::A;
     ///
-    /// let data = A::new(String::from("Hello"));
-    /// {
-    ///     let mut guard = data.write();
-    ///     guard.push_str(", World!");
-    /// } // Lock is released here
-    /// assert_eq!(data.get(), "Hello, World!");
+    /// let data = A::new(42);
+    /// assert_eq!(data.load(), 42);
     /// ```
-    pub fn write(&self) -> ArcRwLockWriteGuard<RawRwLock, T> {
-        self.value.write_arc()
+    pub fn load(&self) -> T
+    where
+        T: Clone,
+    {
+        self.get()
     }
 
-    /// Creates an `A<T>` from an existing `Arc<RwLock<T>>`.
+    /// Sets the inner value to the provided value.
     ///
-    /// This is useful when you already have an `Arc<RwLock<T>>` and want to
-    /// wrap it in the `A<T>` interface.
+    /// This is an alias of [`A::set`] for users coming from `std::sync::atomic`
+    /// or `arc-swap`, where the equivalent operation is called `store`.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use tspawn::A;
-    /// use parking_lot::RwLock;
-    /// use std::sync::Arc;
     ///
-    /// let arc_lock = Arc::new(RwLock::new(42));
-    /// let data = A::from_inner(arc_lock);
-    /// assert_eq!(data.get(), 42);
+    /// let data = A::new(42);
+    /// data.store(100);
+    /// assert_eq!(data.load(), 100);
     /// ```
-    pub fn from_inner(value: Arc<RwLock<T>>) -> Self {
-        A { value }
+    pub fn store(&self, value: T) {
+        self.set(value);
     }
 
-    /// Consumes the `A<T>` and returns the inner `Arc<RwLock<T>>`.
+    /// Replaces the inner value with `T::default()` and returns the previous value.
     ///
-    /// This is useful when you need to work with the underlying `Arc<RwLock<T>>`
-    /// directly or interface with APIs that expect this type.
+    /// This method acquires a write lock and swaps in a fresh default value. It is
+    /// handy for resetting shared state between iterations of a loop. For collections
+    /// whose allocation should be kept, prefer the `reset` method on `A<Vec<T>>` and
+    /// `A<String>`, which clears the contents in place.
     ///
     /// # Examples
     ///
@@ -261,10 +655,1758 @@ impl<T> A<T> {
     /// use tspawn::A;
     ///
     /// let data = A::new(42);
-    /// let arc_lock = data.into_inner();
-    /// assert_eq!(*arc_lock.read(), 42);
+    /// assert_eq!(data.replace_default(), 42);
+    /// assert_eq!(data.get(), 0);
     /// ```
-    pub fn into_inner(self) -> Arc<RwLock<T>> {
-        self.value
+    pub fn replace_default(&self) -> T
+    where
+        T: Default,
+    {
+        let mut guard = self.lock_write();
+        let old = std::mem::take(&mut *guard);
+        self.finish_write(guard);
+        old
+    }
+
+    /// Replaces the inner value with `T::default()` and returns the previous value,
+    /// but only if `pred` holds for it.
+    ///
+    /// The check and the swap happen under a single write lock. For `A<Option<T>>`,
+    /// see `take_if`, which tests the contained value instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let batch = A::new(vec![1, 2]);
+    /// assert_eq!(batch.replace_default_if(|b| b.len() >= 3), None);
+    /// batch.update(|b| b.push(3));
+    /// assert_eq!(batch.replace_default_if(|b| b.len() >= 3), Some(vec![1, 2, 3]));
+    /// assert!(batch.get().is_empty());
+    /// ```
+    pub fn replace_default_if<P>(&self, pred: P) -> Option<T>
+    where
+        T: Default,
+        P: FnOnce(&T) -> bool,
+    {
+        let mut guard = self.lock_write();
+        if !self.run_labeled(|| pred(&guard)) {
+            self.release_write(guard);
+            return None;
+        }
+        let old = std::mem::take(&mut *guard);
+        self.finish_write(guard);
+        Some(old)
+    }
+
+    /// Installs `new` and passes the previous value to `teardown`.
+    ///
+    /// The swap happens under the write lock, which is released *before*
+    /// `teardown` runs. Readers only block for the swap itself, so teardown can be
+    /// slow (closing connections, flushing files) without stalling them, and it
+    /// may even access this handle.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let pool = A::new(vec!["conn-1"]);
+    /// pool.reinit(vec!["conn-2"], |old| {
+    ///     assert_eq!(old, vec!["conn-1"]);
+    /// });
+    /// assert_eq!(pool.get(), vec!["conn-2"]);
+    /// ```
+    pub fn reinit<F>(&self, new: T, teardown: F)
+    where
+        F: FnOnce(T),
+    {
+        let mut guard = self.lock_write();
+        let old = std::mem::replace(&mut *guard, new);
+        self.finish_write(guard);
+        self.run_labeled(|| teardown(old));
+    }
+
+    /// Updates the inner value using a closure.
+    ///
+    /// This method acquires a write lock and calls the provided closure with
+    /// a mutable reference to the inner value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(vec![1, 2, 3]);
+    /// data.update(|v| v.push(4));
+    /// assert_eq!(data.get(), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        let mut guard = self.lock_write();
+        self.run_labeled(|| f(&mut guard));
+        self.finish_write(guard);
+    }
+
+    /// Folds a sequence of events into the value under a single write lock.
+    ///
+    /// Each event is passed to `apply` in order, and the number of events applied
+    /// is returned. Other handles see the state either before or after the whole
+    /// batch, matching event-sourcing replay semantics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// enum Event {
+    ///     Deposit(u64),
+    ///     Withdraw(u64),
+    /// }
+    ///
+    /// let balance = A::new(0);
+    /// let applied = balance.apply_events(
+    ///     [Event::Deposit(50), Event::Withdraw(20)],
+    ///     |balance, event| match event {
+    ///         Event::Deposit(amount) => *balance += amount,
+    ///         Event::Withdraw(amount) => *balance -= amount,
+    ///     },
+    /// );
+    /// assert_eq!(applied, 2);
+    /// assert_eq!(balance.get(), 30);
+    /// ```
+    pub fn apply_events<E, I, F>(&self, events: I, apply: F) -> usize
+    where
+        I: IntoIterator<Item = E>,
+        F: Fn(&mut T, E),
+    {
+        let mut guard = self.lock_write();
+        let applied = self.run_labeled(|| {
+            let mut applied = 0;
+            for event in events {
+                apply(&mut guard, event);
+                applied += 1;
+            }
+            applied
+        });
+        self.finish_write(guard);
+        applied
+    }
+
+    /// Updates the inner value using a closure without ever blocking on the lock.
+    ///
+    /// Each attempt uses a non-blocking `try_write`; if the lock is taken the
+    /// task yields to the runtime and tries again. The worker thread is never
+    /// parked on the lock, at the cost of polling under heavy contention.
+    /// Lock-order checks, owner tracking and contention counting apply as for
+    /// the blocking helpers; a call that has to retry counts as one contended
+    /// write.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let data = A::new(1);
+    /// data.update_async(|x| *x += 1).await;
+    /// assert_eq!(data.get(), 2);
+    /// # }
+    /// ```
+    pub async fn update_async<F>(&self, f: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        #[cfg(feature = "instrumented")]
+        let mut contended = false;
+        loop {
+            if let Some(mut guard) = self.try_lock_write() {
+                self.run_labeled(|| f(&mut guard));
+                self.finish_write(guard);
+                return;
+            }
+            #[cfg(feature = "instrumented")]
+            if !std::mem::replace(&mut contended, true) {
+                self.record_contention();
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+
+    /// Sets the inner value to the maximum of itself and `value`, returning the
+    /// previous value.
+    ///
+    /// This mirrors `AtomicUsize::fetch_max` for any ordered type, which makes it
+    /// a natural fit for high-water marks. Nothing is written if the current
+    /// value is already at least `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let peak = A::new(3);
+    /// assert_eq!(peak.fetch_max(5), 3);
+    /// assert_eq!(peak.fetch_max(4), 5);
+    /// assert_eq!(peak.get(), 5);
+    /// ```
+    pub fn fetch_max(&self, value: T) -> T
+    where
+        T: Ord + Clone,
+    {
+        self.fetch_replace_if(value, |current, value| value > current)
+    }
+
+    /// Sets the inner value to the minimum of itself and `value`, returning the
+    /// previous value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let fastest = A::new(120);
+    /// assert_eq!(fastest.fetch_min(95), 120);
+    /// assert_eq!(fastest.fetch_min(130), 95);
+    /// assert_eq!(fastest.get(), 95);
+    /// ```
+    pub fn fetch_min(&self, value: T) -> T
+    where
+        T: Ord + Clone,
+    {
+        self.fetch_replace_if(value, |current, value| value < current)
+    }
+
+    fn fetch_replace_if(&self, value: T, replace: impl FnOnce(&T, &T) -> bool) -> T
+    where
+        T: Clone,
+    {
+        let mut guard = self.lock_write();
+        let previous = guard.clone();
+        if replace(&guard, &value) {
+            *guard = value;
+            self.finish_write(guard);
+        } else {
+            self.release_write(guard);
+        }
+        previous
+    }
+
+    /// Runs `mutate` under the write lock, then atomically downgrades to a read lock
+    /// and runs `read`.
+    ///
+    /// The lock is never released in between, so `read` observes exactly the state
+    /// `mutate` left behind, while other readers may already proceed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let queue = A::new(vec![1, 2]);
+    /// let len = queue.mutate_then(|q| q.push(3), |q| q.len());
+    /// assert_eq!(len, 3);
+    /// ```
+    pub fn mutate_then<R, M, F>(&self, mutate: M, read: F) -> R
+    where
+        M: FnOnce(&mut T),
+        F: FnOnce(&T) -> R,
+    {
+        let mut guard = self.lock_write();
+        self.run_labeled(|| mutate(&mut guard));
+        self.notify_changed(&guard);
+        let guard = guard.map(RwLockWriteGuard::downgrade);
+        #[cfg(feature = "lock_owner_tracking")]
+        self.clear_writer();
+        let result = self.run_labeled(|| read(&guard));
+        if self.extras.fair {
+            RwLockReadGuard::unlock_fair(guard.into_inner());
+        }
+        result
+    }
+
+    /// Applies `f` only if a selected field of the inner value equals `expect`.
+    ///
+    /// The check and the update happen under a single write lock, so the guard
+    /// condition cannot change between them. Returns `true` if `f` was applied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// #[derive(PartialEq)]
+    /// enum Status { Pending, Done }
+    ///
+    /// struct Job { status: Status, attempts: u32 }
+    ///
+    /// let job = A::new(Job { status: Status::Pending, attempts: 0 });
+    /// let applied = job.update_when(|j| &j.status, &Status::Pending, |j| {
+    ///     j.attempts += 1;
+    ///     j.status = Status::Done;
+    /// });
+    /// assert!(applied);
+    /// assert!(!job.update_when(|j| &j.status, &Status::Pending, |j| j.attempts += 1));
+    /// assert_eq!(job.read().attempts, 1);
+    /// ```
+    pub fn update_when<K, S, F>(&self, select: S, expect: &K, f: F) -> bool
+    where
+        K: PartialEq + ?Sized,
+        S: Fn(&T) -> &K,
+        F: FnOnce(&mut T),
+    {
+        let mut guard = self.lock_write();
+        if self.run_labeled(|| select(&guard) != expect) {
+            self.release_write(guard);
+            return false;
+        }
+        self.run_labeled(|| f(&mut guard));
+        self.finish_write(guard);
+        true
+    }
+
+    /// Computes an update outside the lock and commits it only if the value is unchanged.
+    ///
+    /// The current value is cloned under a short read lock and passed to `f`, which
+    /// returns the new value along with a result. The write lock is then taken and
+    /// the new value is installed only if the stored value still equals the
+    /// snapshot; otherwise the whole cycle is retried. This keeps the write lock
+    /// hold time minimal for expensive computations, at the cost of possibly
+    /// running `f` more than once under contention.
+    ///
+    /// Conflicts are detected by comparing values, so a change that is reverted
+    /// before the commit (A-B-A) is not treated as a conflict.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let counter = A::new(1);
+    /// let previous = counter.update_retry(|current| (current * 2, *current));
+    /// assert_eq!(previous, 1);
+    /// assert_eq!(counter.get(), 2);
+    /// ```
+    pub fn update_retry<R, F>(&self, f: F) -> R
+    where
+        T: Clone + PartialEq,
+        F: Fn(&T) -> (T, R),
+    {
+        loop {
+            let snapshot = self.get();
+            let (new, result) = self.run_labeled(|| f(&snapshot));
+            let mut guard = self.lock_write();
+            if *guard == snapshot {
+                *guard = new;
+                self.finish_write(guard);
+                return result;
+            }
+        }
+    }
+
+    /// Updates the inner value using a closure if the write lock can be acquired
+    /// within `timeout`.
+    ///
+    /// Returns `true` if the lock was acquired and `f` was applied, or `false` if the
+    /// timeout elapsed, in which case the value is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(1);
+    /// assert!(data.try_update_for(Duration::from_millis(10), |x| *x += 1));
+    ///
+    /// let guard = data.read();
+    /// assert!(!data.try_update_for(Duration::from_millis(10), |x| *x += 1));
+    /// drop(guard);
+    /// assert_eq!(data.get(), 2);
+    /// ```
+    #[cfg_attr(feature = "lock_owner_tracking", track_caller)]
+    pub fn try_update_for<F>(&self, timeout: Duration, f: F) -> bool
+    where
+        F: FnOnce(&mut T),
+    {
+        match self.try_lock_write_for(timeout) {
+            Some(mut guard) => {
+                self.run_labeled(|| f(&mut guard));
+                self.finish_write(guard);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Runs `f` against the inner value if a read lock can be acquired within `timeout`.
+    ///
+    /// The read lock is released before returning. If the lock cannot be acquired
+    /// in time, `f` is not run and [`TimedOut`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tspawn::{TimedOut, A};
+    ///
+    /// let data = A::new(vec![1, 2, 3]);
+    /// assert_eq!(data.with_read_timeout(Duration::from_millis(10), |v| v.len()), Ok(3));
+    ///
+    /// let guard = data.write();
+    /// assert_eq!(data.with_read_timeout(Duration::from_millis(10), |v| v.len()), Err(TimedOut));
+    /// # drop(guard);
+    /// ```
+    pub fn with_read_timeout<R, F>(&self, timeout: Duration, f: F) -> Result<R, TimedOut>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let guard = self.try_lock_read_for(timeout).ok_or(TimedOut)?;
+        Ok(self.run_labeled(|| f(&guard)))
+    }
+
+    /// Runs `on_locked` under the write lock if it can be acquired within `timeout`,
+    /// or `on_timeout` otherwise.
+    ///
+    /// Exactly one of the two closures runs, which gives deterministic behavior
+    /// under contention for latency-budgeted loops.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(1);
+    /// let result = data.with_write_or(
+    ///     Duration::from_millis(10),
+    ///     |x| { *x += 1; "updated" },
+    ///     || "skipped",
+    /// );
+    /// assert_eq!(result, "updated");
+    /// ```
+    #[cfg_attr(feature = "lock_owner_tracking", track_caller)]
+    pub fn with_write_or<R, F, G>(&self, timeout: Duration, on_locked: F, on_timeout: G) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+        G: FnOnce() -> R,
+    {
+        match self.try_lock_write_for(timeout) {
+            Some(mut guard) => {
+                let result = self.run_labeled(|| on_locked(&mut guard));
+                self.finish_write(guard);
+                result
+            }
+            None => on_timeout(),
+        }
+    }
+
+    /// Runs `f` under the write lock and returns its result together with how long
+    /// the lock was held.
+    ///
+    /// The duration covers the critical section only, from acquiring the lock to
+    /// releasing it, not the time spent waiting for it. Useful for spot-checking
+    /// hold times in tests and benchmarks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(vec![3, 1, 2]);
+    /// let (len, held) = data.with_write_timed(|v| {
+    ///     v.sort();
+    ///     v.len()
+    /// });
+    /// assert_eq!(len, 3);
+    /// assert!(held < Duration::from_secs(1));
+    /// ```
+    pub fn with_write_timed<R, F>(&self, f: F) -> (R, Duration)
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let mut guard = self.lock_write();
+        let start = Instant::now();
+        let result = self.run_labeled(|| f(&mut guard));
+        self.finish_write(guard);
+        (result, start.elapsed())
+    }
+
+    /// Runs a fallible closure against the inner value under a read lock.
+    ///
+    /// The read lock is released before the closure's result is returned, which
+    /// makes this convenient for validating or extracting from shared state.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let port = A::new(0u16);
+    /// let checked = port.read_and_then(|p| if *p == 0 { Err("unset") } else { Ok(*p) });
+    /// assert_eq!(checked, Err("unset"));
+    /// ```
+    pub fn read_and_then<R, E, F>(&self, f: F) -> Result<R, E>
+    where
+        F: FnOnce(&T) -> Result<R, E>,
+    {
+        let guard = self.lock_read();
+        self.run_labeled(|| f(&guard))
+    }
+
+    /// Mutates the inner value without locking when this is the only handle.
+    ///
+    /// Uses [`Arc::get_mut`] to check for unique ownership: if no other clone (or
+    /// weak reference) exists, `f` is called with direct mutable access and its
+    /// result is returned. Otherwise `f` is not called and `None` is returned.
+    /// This is useful during single-threaded setup before the handle is shared.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let mut data = A::new(vec![1]);
+    /// assert_eq!(data.with_mut_sole(|v| { v.push(2); v.len() }), Some(2));
+    ///
+    /// let shared = data.clone();
+    /// assert_eq!(data.with_mut_sole(|v| v.len()), None);
+    /// # drop(shared);
+    /// ```
+    pub fn with_mut_sole<R, F>(&mut self, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let value = Arc::get_mut(&mut self.value)?.get_mut();
+        Some(self.extras.run_labeled(|| f(value)))
+    }
+
+    /// Locks two handles for writing and runs a closure with mutable access to both.
+    ///
+    /// The locks are always acquired in a globally consistent order (by the
+    /// address of the shared lock, after the lock level with the `lock_order`
+    /// feature), regardless of argument order. Two callers
+    /// locking the same pair in opposite orders therefore cannot deadlock, which
+    /// makes this suitable for transfers between shared containers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` are handles to the same shared value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let alice = A::new(100);
+    /// let bob = A::new(0);
+    ///
+    /// A::with_both_mut(&alice, &bob, |from, to| {
+    ///     *from -= 30;
+    ///     *to += 30;
+    /// });
+    ///
+    /// assert_eq!(alice.get(), 70);
+    /// assert_eq!(bob.get(), 30);
+    /// ```
+    pub fn with_both_mut<U, R, F>(a: &A<T>, b: &A<U>, f: F) -> R
+    where
+        F: FnOnce(&mut T, &mut U) -> R,
+    {
+        assert!(
+            a.lock_addr() != b.lock_addr(),
+            "with_both_mut called with two handles to the same value"
+        );
+        let (mut guard_a, mut guard_b);
+        if a.lock_key() < b.lock_key() {
+            guard_a = a.lock_write();
+            guard_b = b.lock_write();
+        } else {
+            guard_b = b.lock_write();
+            guard_a = a.lock_write();
+        }
+        let result = a.run_labeled(|| b.run_labeled(|| f(&mut guard_a, &mut guard_b)));
+        a.finish_write(guard_a);
+        b.finish_write(guard_b);
+        result
+    }
+
+    /// Write-locks two handles in a globally consistent order and returns both guards.
+    ///
+    /// The locks are *acquired* in order of the shared lock's address, as in
+    /// [`A::with_both_mut`], so callers passing the same pair in opposite orders
+    /// cannot deadlock. With the `lock_order` feature, lower levels are acquired
+    /// first and the address only breaks ties. The guards are *returned* in
+    /// argument order, so the first guard always belongs to `a` and the second to
+    /// `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` are handles to the same shared value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let inbox = A::new(vec![1, 2]);
+    /// let archive = A::new(Vec::new());
+    ///
+    /// let (mut from, mut to) = A::acquire_two(&inbox, &archive);
+    /// to.append(&mut from);
+    /// drop((from, to));
+    ///
+    /// assert_eq!(archive.get(), vec![1, 2]);
+    /// ```
+    pub fn acquire_two<U>(a: &A<T>, b: &A<U>) -> (WriteGuard<T>, WriteGuard<U>) {
+        assert!(
+            a.lock_addr() != b.lock_addr(),
+            "acquire_two called with two handles to the same value"
+        );
+        if a.lock_key() < b.lock_key() {
+            let guard_a = a.write();
+            (guard_a, b.write())
+        } else {
+            let guard_b = b.write();
+            (a.write(), guard_b)
+        }
+    }
+
+    /// Read-locks two handles in address order and runs `f` over a consistent
+    /// snapshot of their values.
+    ///
+    /// This is [`read_all!`](crate::read_all) for the common two-handle case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let balance = A::new(120);
+    /// let limit = A::new(100);
+    /// assert!(A::read2(&balance, &limit, |balance, limit| balance > limit));
+    /// ```
+    pub fn read2<B, R, F>(a: &A<T>, b: &A<B>, f: F) -> R
+    where
+        F: FnOnce(&T, &B) -> R,
+    {
+        a.run_labeled(|| b.run_labeled(|| crate::read_all!(a, b => |a, b| f(a, b))))
+    }
+
+    /// Returns clones of two values taken from one consistent snapshot.
+    ///
+    /// See [`A::read2`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let (name, age) = (A::new(String::from("ada")), A::new(36));
+    /// assert_eq!(A::read2_clone(&name, &age), (String::from("ada"), 36));
+    /// ```
+    pub fn read2_clone<B>(a: &A<T>, b: &A<B>) -> (T, B)
+    where
+        T: Clone,
+        B: Clone,
+    {
+        Self::read2(a, b, |a, b| (a.clone(), b.clone()))
+    }
+
+    /// Read-locks three handles in address order and runs `f` over a consistent
+    /// snapshot of their values.
+    ///
+    /// This is [`read_all!`](crate::read_all) for exactly three handles.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let (x, y, z) = (A::new(1.0_f64), A::new(2.0), A::new(2.0));
+    /// let length = A::read_three(&x, &y, &z, |x, y, z| (x * x + y * y + z * z).sqrt());
+    /// assert_eq!(length, 3.0);
+    /// ```
+    pub fn read_three<B, C, R, F>(a: &A<T>, b: &A<B>, c: &A<C>, f: F) -> R
+    where
+        F: FnOnce(&T, &B, &C) -> R,
+    {
+        a.run_labeled(|| {
+            b.run_labeled(|| c.run_labeled(|| crate::read_all!(a, b, c => |a, b, c| f(a, b, c))))
+        })
+    }
+
+    /// Merges the value of `other` into this one with `combine`.
+    ///
+    /// `self` is locked for writing and `other` for reading, in the same
+    /// address-based order as [`A::with_both_mut`], so concurrent merges in
+    /// opposite directions (`a.merge(&b, ..)` and `b.merge(&a, ..)`) cannot
+    /// deadlock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` are handles to the same shared value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeSet;
+    /// use tspawn::A;
+    ///
+    /// let replica_a = A::new(BTreeSet::from([1, 2]));
+    /// let replica_b = A::new(BTreeSet::from([2, 3]));
+    /// replica_a.merge(&replica_b, |mine, theirs| mine.extend(theirs.iter().copied()));
+    /// assert_eq!(replica_a.get(), BTreeSet::from([1, 2, 3]));
+    /// ```
+    pub fn merge<F>(&self, other: &A<T>, combine: F)
+    where
+        F: FnOnce(&mut T, &T),
+    {
+        assert!(
+            self.lock_addr() != other.lock_addr(),
+            "merge called with two handles to the same value"
+        );
+        let (mut mine, theirs);
+        if self.lock_key() < other.lock_key() {
+            mine = self.lock_write();
+            theirs = other.lock_read();
+        } else {
+            theirs = other.lock_read();
+            mine = self.lock_write();
+        }
+        self.run_labeled(|| other.run_labeled(|| combine(&mut mine, &theirs)));
+        drop(theirs);
+        self.finish_write(mine);
+    }
+
+    /// Folds over the values of several handles, read-locking each in turn.
+    ///
+    /// Only one read lock is held at a time, so the result is not a consistent
+    /// snapshot across handles if they are being written concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let counters = [A::new(1), A::new(2), A::new(3)];
+    /// let total = A::fold(&counters, 0, |acc, x| acc + x);
+    /// assert_eq!(total, 6);
+    /// ```
+    pub fn fold<Acc, F>(handles: &[A<T>], init: Acc, mut f: F) -> Acc
+    where
+        F: FnMut(Acc, &T) -> Acc,
+    {
+        handles.iter().fold(init, |acc, handle| {
+            handle.run_labeled(|| f(acc, &handle.lock_read()))
+        })
+    }
+
+    /// Notifies change observers of a new value; called while the write lock is held.
+    ///
+    /// Mutating helpers call this after modifying the value so that features which
+    /// observe changes see them in order.
+    #[allow(unused_variables)]
+    pub(crate) fn notify_changed(&self, value: &T) {
+        #[cfg(feature = "observers")]
+        if let Some(hook) = &self.extras.write_hook {
+            hook(value);
+        }
+        #[cfg(feature = "stream")]
+        if let Some(changes) = self.extras.changes.get() {
+            if changes.sender.receiver_count() > 0 {
+                // Sending only fails when there are no subscribers, which is fine.
+                let _ = changes.sender.send((changes.clone)(value));
+            }
+            changes.filtered.lock().retain_mut(|send| send(value));
+        }
+    }
+
+    /// Returns a stream of the values written through this handle.
+    ///
+    /// Every mutation made through [`A::set`], [`A::update`] and the other
+    /// mutating helpers publishes a clone of the new value to an internal
+    /// broadcast channel shared by all clones of the handle. Writes made directly
+    /// through a [`A::write`] guard are not observed. The stream only sees changes
+    /// made after it was created; a consumer that falls more than 16 values
+    /// behind skips the missed values, following `tokio::sync::broadcast`
+    /// semantics.
+    ///
+    /// Only available with the `stream` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tokio_stream::StreamExt;
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let data = A::new(0);
+    /// let mut changes = data.changes();
+    ///
+    /// data.set(1);
+    /// assert_eq!(changes.next().await, Some(1));
+    /// # }
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn changes(&self) -> impl tokio_stream::Stream<Item = T> + Unpin + Send + 'static
+    where
+        T: Clone + Send + 'static,
+    {
+        use tokio_stream::StreamExt;
+
+        tokio_stream::wrappers::BroadcastStream::new(self.subscribe_changes())
+            .filter_map(|change| change.ok())
+    }
+
+    /// Returns a stream of the written values that satisfy `pred`.
+    ///
+    /// This is [`A::changes`] with the filter applied on the writing side:
+    /// `pred` runs in the writer's critical section, and only matching values
+    /// are cloned and sent, so writes that do not match never wake the
+    /// consumer. `pred` must therefore be cheap and must not lock this value.
+    /// A consumer that falls 16 matching values behind misses newer matches
+    /// until it catches up.
+    ///
+    /// Only available with the `stream` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tokio_stream::StreamExt;
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let status = A::new("ok");
+    /// let mut errors = status.subscribe_where(|s| s.starts_with("error"));
+    ///
+    /// status.set("busy");
+    /// status.set("error: disk full");
+    /// assert_eq!(errors.next().await, Some("error: disk full"));
+    /// # }
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn subscribe_where<P>(
+        &self,
+        pred: P,
+    ) -> impl tokio_stream::Stream<Item = T> + Unpin + Send + 'static
+    where
+        T: Clone + Send + 'static,
+        P: Fn(&T) -> bool + Send + 'static,
+    {
+        let (sender, receiver) = tokio::sync::mpsc::channel(16);
+        let changes = self.extras.changes.get_or_init(ChangeSender::new);
+        changes.filtered.lock().push(Box::new(move |value: &T| {
+            if sender.is_closed() {
+                return false;
+            }
+            if pred(value) {
+                // A full channel drops the value, as a lagging `changes` stream would.
+                let _ = sender.try_send(value.clone());
+            }
+            true
+        }));
+        tokio_stream::wrappers::ReceiverStream::new(receiver)
+    }
+
+    /// Subscribes to the broadcast channel behind [`A::changes`], creating it on
+    /// first use.
+    #[cfg(feature = "stream")]
+    pub(crate) fn subscribe_changes(&self) -> tokio::sync::broadcast::Receiver<T>
+    where
+        T: Clone,
+    {
+        let changes = self.extras.changes.get_or_init(ChangeSender::new);
+        changes.sender.subscribe()
+    }
+
+    /// Returns a stream that polls the value every `period` and yields it when it changes.
+    ///
+    /// Unlike [`A::changes`], this keeps no state in the handle and also sees
+    /// writes made through [`A::write`] guards, at the cost of latency and of
+    /// missing changes that are reverted between two polls. The current value is
+    /// yielded on the first poll. Must be called from within a Tokio runtime.
+    ///
+    /// Only available with the `stream` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio_stream::StreamExt;
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let data = A::new(0);
+    /// let mut changes = data.poll_changes(Duration::from_millis(5));
+    /// assert_eq!(changes.next().await, Some(0));
+    ///
+    /// *data.write() = 1;
+    /// assert_eq!(changes.next().await, Some(1));
+    /// # }
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn poll_changes(
+        &self,
+        period: Duration,
+    ) -> impl tokio_stream::Stream<Item = T> + Unpin + Send + 'static
+    where
+        T: Clone + PartialEq + Send + Sync + 'static,
+    {
+        use tokio_stream::StreamExt;
+
+        let handle = self.clone();
+        let mut last = None;
+        tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(period)).filter_map(
+            move |_| {
+                let current = handle.get();
+                if last.as_ref() == Some(&current) {
+                    return None;
+                }
+                last = Some(current.clone());
+                Some(current)
+            },
+        )
+    }
+
+    /// Returns a stream that yields a fresh owned write guard each time it is polled.
+    ///
+    /// This supports processing a shared item one step at a time under the lock,
+    /// with the consumer setting the pace. The consumer must drop each guard
+    /// before polling for the next one. While the lock is taken the stream
+    /// sleeps on a tokio timer and retries, doubling the delay up to 10ms, so
+    /// it never parks the worker thread or spins on it. This needs a runtime
+    /// with the time driver enabled. The stream never ends.
+    ///
+    /// Only available with the `stream` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tokio_stream::StreamExt;
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let jobs = A::new(vec!["b", "a"]);
+    /// let mut steps = jobs.write_stream().take(2);
+    /// while let Some(mut guard) = steps.next().await {
+    ///     guard.pop();
+    /// }
+    /// assert!(jobs.get().is_empty());
+    /// # }
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn write_stream(
+        &self,
+    ) -> impl tokio_stream::Stream<Item = WriteGuard<T>> + Unpin + Send + 'static
+    where
+        T: Send + Sync + 'static,
+    {
+        WriteStream {
+            handle: self.clone(),
+            retry: None,
+            backoff: WriteStream::<T>::MIN_BACKOFF,
+        }
+    }
+
+    /// Returns the address identifying the shared lock.
+    pub(crate) fn lock_addr(&self) -> usize {
+        Arc::as_ptr(&self.value) as *const () as usize
+    }
+
+    /// Returns the key used to order lock acquisition across handles.
+    ///
+    /// Locks are taken in address order, except that with the `lock_order`
+    /// feature lower levels come first so the ordering never inverts the
+    /// hierarchy. Handles without a level sort as level 0.
+    pub(crate) fn lock_key(&self) -> (u32, usize) {
+        #[cfg(feature = "lock_order")]
+        let level = self.level().unwrap_or(0);
+        #[cfg(not(feature = "lock_order"))]
+        let level = 0;
+        (level, self.lock_addr())
+    }
+
+    /// Returns a read guard for the inner value.
+    ///
+    /// This allows for more complex read operations without cloning the data.
+    /// The guard will automatically release the lock when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(String::from("Hello"));
+    /// let guard = data.read();
+    /// assert_eq!(&*guard, "Hello");
+    /// // Lock is automatically released when guard is dropped
+    /// ```
+    ///
+    /// The returned guard owns a reference to the shared lock, so it is `'static`
+    /// and can be moved into a hand-written `tokio::spawn(async move { ... })`
+    /// block. See [`A::read_for_task`].
+    pub fn read(&self) -> ReadGuard<T> {
+        #[cfg(feature = "lock_order")]
+        self.check_level();
+        ReadGuard::new(self, self.value.read_arc())
+    }
+
+    /// Returns an owning read guard intended to be moved into a spawned task.
+    ///
+    /// This is the same as [`A::read`]; the name signals that the guard is `Send`
+    /// and `'static`, so a task can be written by hand without `tspawn!`. The read
+    /// lock is held until the task drops the guard.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = A::new(vec![1, 2, 3]);
+    /// let guard = data.read_for_task();
+    ///
+    /// let len = tokio::spawn(async move { guard.len() }).await?;
+    /// assert_eq!(len, 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_for_task(&self) -> ReadGuard<T> {
+        self.read()
+    }
+
+    /// Returns a write guard for the inner value.
+    ///
+    /// This allows for more complex write operations. The guard will
+    /// automatically release the lock when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(String::from("Hello"));
+    /// {
+    ///     let mut guard = data.write();
+    ///     guard.push_str(", World!");
+    /// } // Lock is released here
+    /// assert_eq!(data.get(), "Hello, World!");
+    /// ```
+    #[cfg_attr(feature = "lock_owner_tracking", track_caller)]
+    pub fn write(&self) -> WriteGuard<T> {
+        #[cfg(feature = "lock_order")]
+        self.check_level();
+        #[cfg(feature = "instrumented")]
+        let guard = match self.value.try_write_arc() {
+            Some(guard) => guard,
+            None => {
+                self.record_contention();
+                self.value.write_arc()
+            }
+        };
+        #[cfg(not(feature = "instrumented"))]
+        let guard = self.value.write_arc();
+        #[cfg(feature = "lock_owner_tracking")]
+        self.record_writer(Location::caller());
+        WriteGuard::new(self, guard)
+    }
+
+    /// Attempts to acquire an owning read guard without blocking.
+    ///
+    /// Returns `None` if the lock is currently held for writing. The returned guard
+    /// is `'static` and `Send`, so it can be moved into a spawned task.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// let guard = data.try_read_arc().expect("lock is free");
+    /// assert_eq!(*guard, 42);
+    /// ```
+    pub fn try_read_arc(&self) -> Option<ReadGuard<T>> {
+        let guard = self.value.try_read_arc()?;
+        Some(ReadGuard::new(self, guard))
+    }
+
+    /// Attempts to acquire an owning read guard without blocking, even if a writer
+    /// is waiting.
+    ///
+    /// Unlike [`A::try_read_arc`], this succeeds while a writer is queued behind
+    /// existing readers, so a thread that already holds a read guard can take a
+    /// nested one without deadlocking against that writer. It still returns
+    /// `None` if a writer currently holds the lock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let tree = A::new(vec![1, 2, 3]);
+    /// let outer = tree.try_read_recursive().expect("lock is free");
+    /// let inner = tree.try_read_recursive().expect("shared with outer");
+    /// assert_eq!(outer.len(), inner.len());
+    /// ```
+    pub fn try_read_recursive(&self) -> Option<ReadGuard<T>> {
+        let guard = self.value.try_read_recursive_arc()?;
+        Some(ReadGuard::new(self, guard))
+    }
+
+    /// Attempts to acquire an owning write guard without blocking.
+    ///
+    /// Returns `None` if the lock is currently held. This supports a "spawn only if
+    /// the lock is free now" pattern: on success the guard can be moved straight
+    /// into a spawned task, otherwise the work can be skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// let reader = data.read();
+    /// assert!(data.try_write_arc().is_none());
+    /// drop(reader);
+    /// assert!(data.try_write_arc().is_some());
+    /// ```
+    #[cfg_attr(feature = "lock_owner_tracking", track_caller)]
+    pub fn try_write_arc(&self) -> Option<WriteGuard<T>> {
+        let guard = self.value.try_write_arc()?;
+        #[cfg(feature = "lock_owner_tracking")]
+        self.record_writer(Location::caller());
+        Some(WriteGuard::new(self, guard))
+    }
+
+    /// Attempts to acquire an upgradable read lock within `timeout`.
+    ///
+    /// An upgradable read coexists with plain readers but excludes writers and
+    /// other upgradable readers, so a decision made while holding it stays valid
+    /// after upgrading. Use `ArcRwLockUpgradableReadGuard::try_upgrade_for` to
+    /// bound the wait for exclusive access as well; on timeout it hands the
+    /// upgradable guard back so the caller can bail out gracefully.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use parking_lot::ArcRwLockUpgradableReadGuard;
+    /// use std::time::Duration;
+    /// use tspawn::A;
+    ///
+    /// let cache = A::new(None::<String>);
+    /// let budget = Duration::from_millis(10);
+    ///
+    /// if let Some(guard) = cache.try_upgradable_read_for(budget) {
+    ///     if guard.is_none() {
+    ///         match ArcRwLockUpgradableReadGuard::try_upgrade_for(guard, budget) {
+    ///             Ok(mut write) => *write = Some(String::from("filled")),
+    ///             Err(_still_reading) => { /* give up this round */ }
+    ///         }
+    ///     }
+    /// }
+    /// assert_eq!(cache.get().as_deref(), Some("filled"));
+    /// ```
+    pub fn try_upgradable_read_for(
+        &self,
+        timeout: Duration,
+    ) -> Option<ArcRwLockUpgradableReadGuard<RawRwLock, T>> {
+        #[cfg(feature = "lock_order")]
+        self.check_level();
+        let guard = self.value.try_upgradable_read_arc_for(timeout)?;
+        // No writer can hold the lock now, and an upgrade of this guard is not
+        // recorded, so forget the previous owner rather than report it later.
+        #[cfg(feature = "lock_owner_tracking")]
+        self.clear_writer();
+        Some(guard)
+    }
+
+    /// Returns a write guard scoped to a part of the inner value, if `f` selects one.
+    ///
+    /// The write lock is acquired and `f` picks a sub-object, typically the payload
+    /// of one enum variant. If `f` returns `None`, the lock is released and `None`
+    /// is returned. The mapped guard borrows this handle, since `parking_lot` only
+    /// supports mapping borrowed guards.
+    ///
+    /// Writes made through the returned guard are not published to
+    /// [`A::changes`] subscribers or write hooks; use [`A::update`] when they need
+    /// to be observed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// enum Message {
+    ///     Text(String),
+    ///     Ping,
+    /// }
+    ///
+    /// let message = A::new(Message::Text(String::from("hi")));
+    /// let text = message.write_variant(|m| match m {
+    ///     Message::Text(text) => Some(text),
+    ///     Message::Ping => None,
+    /// });
+    /// text.unwrap().push('!');
+    /// ```
+    pub fn write_variant<U, F>(&self, f: F) -> Option<MappedWriteGuard<'_, U>>
+    where
+        U: ?Sized,
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        let guard = self.lock_write().try_map(|guard| {
+            RwLockWriteGuard::try_map(guard, |value| self.run_labeled(|| f(value)))
+        });
+        match guard {
+            Ok(guard) => Some(MappedWriteGuard::new(self, guard)),
+            Err(guard) => {
+                self.release_write(guard);
+                None
+            }
+        }
+    }
+
+    /// Acquires a borrowed write guard, recording contention when instrumented.
+    ///
+    /// All internal write paths go through this helper so that features which
+    /// observe write locking see every mutation made through the `A<T>` API.
+    #[cfg_attr(feature = "lock_owner_tracking", track_caller)]
+    pub(crate) fn lock_write(&self) -> Locked<RwLockWriteGuard<'_, T>> {
+        #[cfg(feature = "lock_order")]
+        self.check_level();
+        #[cfg(feature = "instrumented")]
+        let guard = match self.value.try_write() {
+            Some(guard) => guard,
+            None => {
+                self.record_contention();
+                self.value.write()
+            }
+        };
+        #[cfg(not(feature = "instrumented"))]
+        let guard = self.value.write();
+        #[cfg(feature = "lock_owner_tracking")]
+        self.record_writer(Location::caller());
+        Locked::new(self, guard)
+    }
+
+    /// Attempts to acquire a borrowed write guard without blocking, with the same
+    /// lock-order check and owner recording as [`A::lock_write`].
+    ///
+    /// A failed attempt is not counted as contention; callers that go on to wait
+    /// for the lock record it themselves.
+    #[cfg_attr(feature = "lock_owner_tracking", track_caller)]
+    pub(crate) fn try_lock_write(&self) -> Option<Locked<RwLockWriteGuard<'_, T>>> {
+        #[cfg(feature = "lock_order")]
+        self.check_level();
+        let guard = self.value.try_write()?;
+        #[cfg(feature = "lock_owner_tracking")]
+        self.record_writer(Location::caller());
+        Some(Locked::new(self, guard))
+    }
+
+    #[cfg(feature = "instrumented")]
+    fn record_contention(&self) {
+        if self.extras.count_contention {
+            self.extras.write_contention.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Acquires a borrowed write guard within `timeout`, with the same lock-order
+    /// check and owner recording as [`A::lock_write`].
+    #[cfg_attr(feature = "lock_owner_tracking", track_caller)]
+    pub(crate) fn try_lock_write_for(
+        &self,
+        timeout: Duration,
+    ) -> Option<Locked<RwLockWriteGuard<'_, T>>> {
+        #[cfg(feature = "lock_order")]
+        self.check_level();
+        let guard = self.value.try_write_for(timeout)?;
+        #[cfg(feature = "lock_owner_tracking")]
+        self.record_writer(Location::caller());
+        Some(Locked::new(self, guard))
+    }
+
+    /// Acquires a borrowed read guard.
+    ///
+    /// All internal read paths go through this helper, so the `lock_order`
+    /// check covers them and the level stays held while the guard is alive.
+    pub(crate) fn lock_read(&self) -> Locked<RwLockReadGuard<'_, T>> {
+        #[cfg(feature = "lock_order")]
+        self.check_level();
+        Locked::new(self, self.value.read())
+    }
+
+    /// Acquires a borrowed read guard that ignores queued writers, as `read_all!`
+    /// needs for handles listed twice.
+    pub(crate) fn lock_read_recursive(&self) -> Locked<RwLockReadGuard<'_, T>> {
+        #[cfg(feature = "lock_order")]
+        self.check_level();
+        Locked::new(self, self.value.read_recursive())
+    }
+
+    /// Attempts to acquire a borrowed read guard without blocking.
+    ///
+    /// A failed attempt cannot deadlock, so only a successful one is tracked.
+    pub(crate) fn try_lock_read(&self) -> Option<Locked<RwLockReadGuard<'_, T>>> {
+        let guard = self.value.try_read()?;
+        Some(Locked::new(self, guard))
+    }
+
+    /// Acquires a borrowed read guard within `timeout`, checked like
+    /// [`A::lock_read`].
+    pub(crate) fn try_lock_read_for(
+        &self,
+        timeout: Duration,
+    ) -> Option<Locked<RwLockReadGuard<'_, T>>> {
+        #[cfg(feature = "lock_order")]
+        self.check_level();
+        let guard = self.value.try_read_for(timeout)?;
+        Some(Locked::new(self, guard))
+    }
+
+    /// Runs `f`, adding this handle's label to the message of any panic it raises.
+    pub(crate) fn run_labeled<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.extras.run_labeled(f)
+    }
+
+    /// Publishes a change made through `guard` and releases the write lock,
+    /// handing it off fairly if this handle was built with [`ABuilder::fair`].
+    ///
+    /// [`ABuilder::fair`]: crate::ABuilder::fair
+    pub(crate) fn finish_write(&self, guard: Locked<RwLockWriteGuard<'_, T>>) {
+        self.notify_changed(&guard);
+        self.release_write(guard);
+    }
+
+    /// Releases the write lock without publishing, for helpers that end up
+    /// leaving the value untouched.
+    pub(crate) fn release_write(&self, guard: Locked<RwLockWriteGuard<'_, T>>) {
+        #[cfg(feature = "lock_owner_tracking")]
+        self.clear_writer();
+        if self.extras.fair {
+            RwLockWriteGuard::unlock_fair(guard.into_inner());
+        }
+    }
+
+    #[cfg(feature = "lock_owner_tracking")]
+    fn record_writer(&self, location: &'static Location<'static>) {
+        *self.extras.writer.lock() = Some((std::thread::current().id(), location));
+    }
+
+    #[cfg(feature = "lock_owner_tracking")]
+    fn clear_writer(&self) {
+        *self.extras.writer.lock() = None;
+    }
+
+    /// Returns the thread currently holding the write lock, if any.
+    ///
+    /// The owner is recorded whenever the `A<T>` API takes the write lock: through
+    /// [`A::write`], [`A::try_write_arc`], the write helpers such as [`A::set`],
+    /// [`A::update`] and [`A::try_update_for`], and `tspawn!` write captures. It is
+    /// cleared when a helper releases the lock or a [`WriteGuard`] is dropped, and
+    /// is only reported while the lock is actually held for writing. A write lock obtained by upgrading an
+    /// [`A::try_upgradable_read_for`] guard, or through the raw `Arc<RwLock<T>>`,
+    /// has no recorded owner and reports `None`. This is a best-effort diagnostic
+    /// for tracking down deadlocks.
+    ///
+    /// Only available with the `lock_owner_tracking` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(0);
+    /// let guard = data.write();
+    /// assert_eq!(data.current_writer(), Some(std::thread::current().id()));
+    /// drop(guard);
+    /// assert_eq!(data.current_writer(), None);
+    /// ```
+    #[cfg(feature = "lock_owner_tracking")]
+    pub fn current_writer(&self) -> Option<ThreadId> {
+        self.current_writer_location().map(|(thread, _)| thread)
+    }
+
+    /// Returns the thread and source location of the current write lock holder.
+    ///
+    /// See [`A::current_writer`] for when the owner is recorded.
+    ///
+    /// Only available with the `lock_owner_tracking` feature.
+    #[cfg(feature = "lock_owner_tracking")]
+    pub fn current_writer_location(&self) -> Option<(ThreadId, &'static Location<'static>)> {
+        let owner = *self.extras.writer.lock();
+        if self.value.is_locked_exclusive() {
+            owner
+        } else {
+            None
+        }
+    }
+
+    /// Returns how many write lock acquisitions had to block.
+    ///
+    /// A write acquisition counts as contended when an initial non-blocking
+    /// attempt fails and the call falls back to waiting for the lock. The counter
+    /// is shared by all clones of this handle; handles created separately via
+    /// [`A::from_inner`] over the same lock keep their own counter.
+    ///
+    /// Only available with the `instrumented` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(0);
+    /// data.set(1);
+    /// assert_eq!(data.write_contention(), 0);
+    /// ```
+    #[cfg(feature = "instrumented")]
+    pub fn write_contention(&self) -> u64 {
+        self.extras.write_contention.load(Ordering::Relaxed)
+    }
+
+    /// Returns a clone of this handle if `cond` is `true`, or `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// let targets: Vec<_> = [true, false, true].iter().filter_map(|&c| data.clone_if(c)).collect();
+    /// assert_eq!(targets.len(), 2);
+    /// ```
+    pub fn clone_if(&self, cond: bool) -> Option<A<T>> {
+        cond.then(|| self.clone())
+    }
+
+    /// Returns a new, independent handle holding a modified copy of the current value.
+    ///
+    /// The value is cloned under a read lock, the lock is released, and `f` is
+    /// applied to the copy. Unlike [`Clone::clone`], the result does not share
+    /// storage with this handle: writes to either one are invisible to the other,
+    /// which makes it suitable for speculative or what-if computation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let prices = A::new(vec![10, 20]);
+    /// let discounted = prices.fork(|p| p.iter_mut().for_each(|x| *x /= 2));
+    /// assert_eq!(discounted.get(), vec![5, 10]);
+    /// assert_eq!(prices.get(), vec![10, 20]);
+    /// ```
+    pub fn fork<F>(&self, f: F) -> A<T>
+    where
+        T: Clone,
+        F: FnOnce(&mut T),
+    {
+        let mut value = self.lock_read().clone();
+        self.run_labeled(|| f(&mut value));
+        A::new(value)
+    }
+
+    /// Returns an owned closure that yields a clone of the current value on each call.
+    ///
+    /// The closure holds its own clone of the handle, which makes it a drop-in
+    /// for APIs that take a `Fn() -> T` provider.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// fn render(title: impl Fn() -> String) -> String {
+    ///     format!("<h1>{}</h1>", title())
+    /// }
+    ///
+    /// let title = A::new(String::from("Home"));
+    /// let provider = title.reader_fn();
+    /// title.set(String::from("About"));
+    /// assert_eq!(render(provider), "<h1>About</h1>");
+    /// ```
+    pub fn reader_fn(&self) -> impl Fn() -> T + Clone + Send + Sync + 'static
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let handle = self.clone();
+        move || handle.get()
+    }
+
+    /// Repoints this handle at the value shared by `other`.
+    ///
+    /// Afterwards `self` and `other` share storage, as if `self` were a fresh
+    /// clone of `other`. Clones made from `self` *before* the call are unaffected
+    /// and keep pointing at the old value, which is dropped once they are gone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let mut active = A::new("staging");
+    /// let production = A::new("production");
+    /// active.rebind(&production);
+    /// assert_eq!(active.as_ptr(), production.as_ptr());
+    /// ```
+    pub fn rebind(&mut self, other: &A<T>) {
+        *self = other.clone();
+    }
+
+    /// Returns a raw pointer to the shared lock, identifying the allocation.
+    ///
+    /// All clones of a handle return the same pointer, so it can be used as a
+    /// stable identity key without requiring `T: Hash`. See [`ByAddress`] for a
+    /// ready-made map key.
+    ///
+    /// [`ByAddress`]: crate::ByAddress
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// let cloned = data.clone();
+    /// assert_eq!(data.as_ptr(), cloned.as_ptr());
+    /// assert_ne!(data.as_ptr(), A::new(42).as_ptr());
+    /// ```
+    pub fn as_ptr(&self) -> *const RwLock<T> {
+        Arc::as_ptr(&self.value)
+    }
+
+    /// Returns a raw pointer to the inner value without locking.
+    ///
+    /// Dereferencing the pointer is only sound while no conflicting lock is held;
+    /// it is intended for identity checks and FFI hand-off.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// assert_eq!(data.data_ptr() as *const i32, &*data.read() as *const i32);
+    /// ```
+    pub fn data_ptr(&self) -> *mut T {
+        self.value.data_ptr()
+    }
+
+    /// Creates an `A<T>` from an existing `Arc<RwLock<T>>`.
+    ///
+    /// This is useful when you already have an `Arc<RwLock<T>>` and want to
+    /// wrap it in the `A<T>` interface.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    /// use parking_lot::RwLock;
+    /// use std::sync::Arc;
+    ///
+    /// let arc_lock = Arc::new(RwLock::new(42));
+    /// let data = A::from_inner(arc_lock);
+    /// assert_eq!(data.get(), 42);
+    /// ```
+    pub fn from_inner(value: Arc<RwLock<T>>) -> Self {
+        A {
+            value,
+            extras: Extras::new(),
+        }
+    }
+
+    /// Creates an `A<T>` from an unshared `RwLock<T>`, moving it into a fresh `Arc`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use parking_lot::RwLock;
+    /// use tspawn::A;
+    ///
+    /// let data = A::from_rwlock(RwLock::new(42));
+    /// assert_eq!(data.get(), 42);
+    /// ```
+    pub fn from_rwlock(lock: RwLock<T>) -> Self {
+        Self::from_inner(Arc::new(lock))
+    }
+
+    /// Consumes the `A<T>` and returns the inner `Arc<RwLock<T>>`.
+    ///
+    /// This is useful when you need to work with the underlying `Arc<RwLock<T>>`
+    /// directly or interface with APIs that expect this type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// let arc_lock = data.into_inner();
+    /// assert_eq!(*arc_lock.read(), 42);
+    /// ```
+    pub fn into_inner(self) -> Arc<RwLock<T>> {
+        self.value
+    }
+
+    /// Returns the inner value if this is the only handle, or the handle otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(self)` if any other clone of this handle is still alive.
+    /// Weak references such as [`WeakA`](crate::WeakA) do not count; they simply
+    /// fail to upgrade afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// let cloned = data.clone();
+    /// let data = data.try_unwrap().err().unwrap();
+    ///
+    /// drop(cloned);
+    /// assert_eq!(data.try_unwrap().ok(), Some(42));
+    /// ```
+    pub fn try_unwrap(self) -> Result<T, A<T>> {
+        let extras = self.extras;
+        match Arc::try_unwrap(self.value) {
+            Ok(lock) => Ok(lock.into_inner()),
+            Err(value) => Err(A { value, extras }),
+        }
+    }
+
+    /// Waits up to `timeout` for this to become the only handle, then returns the
+    /// inner value.
+    ///
+    /// Checks every `poll` whether the other clones have been dropped, blocking
+    /// the current thread in between; call it from `spawn_blocking` inside async
+    /// code. This bounds how long shutdown waits on a leaked clone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(self)` if other handles are still alive after `timeout`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// let worker = data.clone();
+    /// std::thread::spawn(move || drop(worker));
+    ///
+    /// let value = data.into_inner_value_timeout(Duration::from_secs(1), Duration::from_millis(1));
+    /// assert_eq!(value.ok(), Some(42));
+    /// ```
+    pub fn into_inner_value_timeout(self, timeout: Duration, poll: Duration) -> Result<T, A<T>> {
+        let deadline = Instant::now() + timeout;
+        let mut handle = self;
+        loop {
+            handle = match handle.try_unwrap() {
+                Ok(value) => return Ok(value),
+                Err(handle) => handle,
+            };
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(handle);
+            }
+            std::thread::sleep(poll.min(deadline - now));
+        }
+    }
+}
+
+impl<T> A<Option<T>> {
+    /// Takes the contained value, leaving `None`, but only if `pred` holds for it.
+    ///
+    /// Returns `None` without touching the value if it is already `None` or the
+    /// predicate fails. The check and the take happen under a single write lock,
+    /// so two tasks cannot both take the same value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let slot = A::new(Some(5));
+    /// assert_eq!(slot.take_if(|n| *n > 10), None);
+    /// assert_eq!(slot.take_if(|n| *n > 1), Some(5));
+    /// assert_eq!(slot.get(), None);
+    /// ```
+    pub fn take_if<P>(&self, pred: P) -> Option<T>
+    where
+        P: FnOnce(&T) -> bool,
+    {
+        let mut guard = self.lock_write();
+        if !self.run_labeled(|| guard.as_ref().is_some_and(pred)) {
+            self.release_write(guard);
+            return None;
+        }
+        let taken = guard.take();
+        self.finish_write(guard);
+        taken
+    }
+}
+
+impl<T, E> A<Result<T, E>> {
+    /// Returns `true` if the stored result is `Ok`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let conn: A<Result<u32, String>> = A::new(Ok(1));
+    /// assert!(conn.is_ok());
+    /// ```
+    pub fn is_ok(&self) -> bool {
+        self.lock_read().is_ok()
+    }
+
+    /// Returns a clone of the `Ok` value, or `None` if the stored result is `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let conn: A<Result<u32, String>> = A::new(Err("offline".into()));
+    /// assert_eq!(conn.ok_cloned(), None);
+    /// ```
+    pub fn ok_cloned(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.lock_read().as_ref().ok().cloned()
+    }
+
+    /// Installs `Ok(value)` and returns the previously stored result.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let conn: A<Result<u32, String>> = A::new(Err("offline".into()));
+    /// assert_eq!(conn.replace_ok(7), Err("offline".to_string()));
+    /// assert_eq!(conn.ok_cloned(), Some(7));
+    /// ```
+    pub fn replace_ok(&self, value: T) -> Result<T, E> {
+        let mut guard = self.lock_write();
+        let previous = std::mem::replace(&mut *guard, Ok(value));
+        self.finish_write(guard);
+        previous
     }
 }
@@ -0,0 +1,47 @@
+//! Task handles that cancel their task when dropped.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::task::{JoinError, JoinHandle};
+
+/// A task handle that aborts the task when it is dropped.
+///
+/// Created by [`A::spawn_guarded`](crate::A::spawn_guarded). Awaiting it
+/// yields the task's output like a [`JoinHandle`]. Dropping it before the task
+/// finishes aborts the task, so a background task cannot outlive the scope
+/// that owns it; call [`AbortOnDrop::detach`] to let the task keep running.
+pub struct AbortOnDrop<R> {
+    handle: Option<JoinHandle<R>>,
+}
+
+impl<R> AbortOnDrop<R> {
+    /// Wraps a task handle so the task is aborted when the wrapper is dropped.
+    pub fn new(handle: JoinHandle<R>) -> Self {
+        AbortOnDrop {
+            handle: Some(handle),
+        }
+    }
+
+    /// Releases the task so it keeps running, returning its plain handle.
+    pub fn detach(mut self) -> JoinHandle<R> {
+        self.handle.take().expect("handle is only taken once")
+    }
+}
+
+impl<R> Future for AbortOnDrop<R> {
+    type Output = Result<R, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let handle = self.handle.as_mut().expect("handle is only taken once");
+        Pin::new(handle).poll(cx)
+    }
+}
+
+impl<R> Drop for AbortOnDrop<R> {
+    fn drop(&mut self) {
+        if let Some(handle) = &self.handle {
+            handle.abort();
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Type-erased storage for `A<T>` handles.
+
+use crate::A;
+use std::any::Any;
+
+/// A type-erased [`A<T>`] handle that can be downcast back to its concrete type.
+///
+/// Created with [`A::into_any`]. This supports registries of heterogeneous shared
+/// states, where each entry is stored as an `AnyA` and recovered with
+/// [`AnyA::downcast`] or borrowed with [`AnyA::downcast_ref`].
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{A, AnyA};
+///
+/// let registry: Vec<AnyA> = vec![A::new(1).into_any(), A::new(String::from("x")).into_any()];
+///
+/// assert_eq!(registry[0].downcast_ref::<i32>().unwrap().get(), 1);
+/// assert!(registry[1].downcast_ref::<i32>().is_none());
+/// ```
+pub struct AnyA {
+    handle: Box<dyn Any + Send + Sync>,
+}
+
+impl AnyA {
+    /// Returns `true` if this wraps an `A<T>`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.handle.is::<A<T>>()
+    }
+
+    /// Returns a reference to the wrapped `A<T>`, or `None` if the type differs.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&A<T>> {
+        self.handle.downcast_ref::<A<T>>()
+    }
+
+    /// Recovers the wrapped `A<T>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` unchanged if the wrapped handle is not an `A<T>`.
+    pub fn downcast<T: 'static>(self) -> Result<A<T>, AnyA> {
+        match self.handle.downcast::<A<T>>() {
+            Ok(handle) => Ok(*handle),
+            Err(handle) => Err(AnyA { handle }),
+        }
+    }
+}
+
+impl<T> A<T>
+where
+    T: Send + Sync + 'static,
+{
+    /// Erases the value type of this handle, producing an [`AnyA`].
+    ///
+    /// The shared value is not touched; other clones keep working as before.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// let erased = data.clone().into_any();
+    /// let restored = erased.downcast::<i32>().ok().unwrap();
+    ///
+    /// restored.set(7);
+    /// assert_eq!(data.get(), 7);
+    /// ```
+    pub fn into_any(self) -> AnyA {
+        AnyA {
+            handle: Box::new(self),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Mutations queued up and applied together in one critical section.
+
+use crate::A;
+
+type QueuedUpdate<'a, T> = Box<dyn FnOnce(&mut T) + 'a>;
+
+/// A queue of mutations applied to an [`A<T>`] under a single write lock.
+///
+/// Created by [`A::batched`]. Closures added with [`UpdateBatch::push`] do not
+/// touch the value until the batch is committed, either explicitly with
+/// [`UpdateBatch::commit`] or implicitly when the batch is dropped. They then
+/// run in the order they were pushed while the write lock is held, so other
+/// readers see either none or all of the batch.
+///
+/// Only `commit` or a normal drop publishes the batch. If the batch is dropped
+/// while the thread is unwinding from a panic, the queued mutations are
+/// discarded rather than applied on top of a half-finished operation.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::A;
+///
+/// let cart = A::new(Vec::new());
+/// let mut batch = cart.batched();
+/// batch.push(|items| items.push("apple"));
+/// batch.push(|items| items.push("pear"));
+/// assert!(cart.get().is_empty());
+///
+/// batch.commit();
+/// assert_eq!(cart.get(), vec!["apple", "pear"]);
+/// ```
+pub struct UpdateBatch<'a, T> {
+    target: &'a A<T>,
+    updates: Vec<QueuedUpdate<'a, T>>,
+}
+
+impl<'a, T> UpdateBatch<'a, T> {
+    /// Queues a mutation to run when the batch is committed.
+    pub fn push<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut T) + 'a,
+    {
+        self.updates.push(Box::new(f));
+    }
+
+    /// Returns the number of queued mutations.
+    pub fn len(&self) -> usize {
+        self.updates.len()
+    }
+
+    /// Returns `true` if no mutations are queued.
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Applies all queued mutations under one write lock.
+    ///
+    /// Equivalent to dropping the batch, but makes the commit point explicit.
+    pub fn commit(self) {}
+
+    fn apply(&mut self) {
+        if self.updates.is_empty() {
+            return;
+        }
+        let updates = std::mem::take(&mut self.updates);
+        let mut guard = self.target.lock_write();
+        self.target.run_labeled(|| {
+            for update in updates {
+                update(&mut guard);
+            }
+        });
+        self.target.finish_write(guard);
+    }
+}
+
+impl<T> Drop for UpdateBatch<'_, T> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        self.apply();
+    }
+}
+
+impl<T> A<T> {
+    /// Starts an [`UpdateBatch`] that applies queued mutations to this value in a
+    /// single critical section.
+    ///
+    /// Useful for accumulating changes across a function and publishing them
+    /// atomically.
+    pub fn batched(&self) -> UpdateBatch<'_, T> {
+        UpdateBatch {
+            target: self,
+            updates: Vec::new(),
+        }
+    }
+}
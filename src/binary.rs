@@ -0,0 +1,43 @@
+//! Binary snapshots of `A<T>` values using bincode.
+
+use crate::A;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+impl<T> A<T> {
+    /// Serializes the inner value with bincode under a read lock.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `T`'s `Serialize` implementation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let scores = A::new(vec![10u32, 20]);
+    /// let bytes = scores.to_bytes()?;
+    /// let restored: A<Vec<u32>> = A::from_bytes(&bytes)?;
+    /// assert_eq!(restored.get(), vec![10, 20]);
+    /// # Ok::<(), bincode::Error>(())
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error>
+    where
+        T: Serialize,
+    {
+        bincode::serialize(&*self.lock_read())
+    }
+
+    /// Deserializes a value written by [`A::to_bytes`] into a new handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid bincode encoding of `T`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error>
+    where
+        T: DeserializeOwned,
+    {
+        bincode::deserialize(bytes).map(A::new)
+    }
+}
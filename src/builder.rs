@@ -0,0 +1,177 @@
+//! Builder for configuring how an `A<T>` locks and reports.
+
+use crate::a::{Extras, A};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Configures and constructs an [`A<T>`].
+///
+/// Created with [`A::builder`]. Options that depend on a disabled cargo feature
+/// are accepted and ignored, so the same construction code compiles under any
+/// feature set.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::A;
+///
+/// let counter = A::builder(0).fair(true).label("requests").build();
+/// counter.update(|x| *x += 1);
+/// assert_eq!(counter.get(), 1);
+/// ```
+pub struct ABuilder<T> {
+    value: T,
+    fair: bool,
+    label: Option<Arc<str>>,
+    instrumented: bool,
+}
+
+impl<T> ABuilder<T> {
+    /// Makes the write helpers such as [`A::set`] and [`A::update`] release the
+    /// lock with a fair unlock, handing it directly to the next waiter.
+    ///
+    /// This trades throughput for predictable latency when many tasks compete
+    /// for the same value. Guards returned by [`A::write`] are unaffected.
+    pub fn fair(mut self, fair: bool) -> Self {
+        self.fair = fair;
+        self
+    }
+
+    /// Attaches a name to the value, available through `A::label` when the
+    /// `labeled` feature is enabled.
+    pub fn label(mut self, label: impl Into<Arc<str>>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Enables or disables write contention counting.
+    ///
+    /// Counting is on by default when the `instrumented` feature is enabled;
+    /// without the feature this option has no effect.
+    pub fn instrumented(mut self, instrumented: bool) -> Self {
+        self.instrumented = instrumented;
+        self
+    }
+
+    /// Builds the configured `A<T>`.
+    pub fn build(self) -> A<T> {
+        #[cfg_attr(
+            not(any(feature = "labeled", feature = "instrumented")),
+            allow(unused_mut)
+        )]
+        let mut extras = Extras::new();
+        extras.fair = self.fair;
+        #[cfg(feature = "labeled")]
+        {
+            extras.label = self.label;
+        }
+        #[cfg(feature = "instrumented")]
+        {
+            extras.count_contention = self.instrumented;
+        }
+        #[cfg(not(feature = "labeled"))]
+        let _ = self.label;
+        #[cfg(not(feature = "instrumented"))]
+        let _ = self.instrumented;
+        A {
+            value: Arc::new(RwLock::new(self.value)),
+            extras,
+        }
+    }
+}
+
+impl<T> A<T> {
+    /// Returns a builder for configuring a new `A<T>` wrapping `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::builder(String::new()).fair(true).build();
+    /// data.set(String::from("ready"));
+    /// assert_eq!(data.get(), "ready");
+    /// ```
+    pub fn builder(value: T) -> ABuilder<T> {
+        ABuilder {
+            value,
+            fair: false,
+            label: None,
+            instrumented: true,
+        }
+    }
+
+    /// Tags this handle with a label, returned by [`A::label`].
+    ///
+    /// Panics raised by a closure passed to any synchronous helper of this
+    /// handle, such as [`A::update`], [`A::read_and_then`] or [`A::update_when`], are
+    /// re-raised with the label prepended to the message, so logs show which
+    /// shared value was being accessed. Helpers over several handles, such as
+    /// [`A::with_both_mut`], prepend each handle's label. Closures run by spawned
+    /// tasks or async helpers are not covered. The label
+    /// belongs to this handle and clones made from it afterwards; existing
+    /// clones keep their own label.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let orders = A::new(Vec::<u32>::new()).with_label("orders");
+    /// assert_eq!(orders.label(), Some("orders"));
+    /// ```
+    #[cfg(feature = "labeled")]
+    pub fn with_label(mut self, label: impl Into<Arc<str>>) -> Self {
+        self.extras.label = Some(label.into());
+        self
+    }
+
+    /// Installs a hook called with the new value after every mutation made
+    /// through [`A::set`], [`A::update`], [`A::replace_default`] and the other
+    /// mutating helpers.
+    ///
+    /// The hook runs while the write lock is still held, so it observes writes
+    /// in exactly the order they were applied and must not lock this value
+    /// again. Writes made directly through a guard from [`A::write`] are not
+    /// reported. Like a label, the hook belongs to this handle and clones made
+    /// from it afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use tspawn::A;
+    ///
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let audit = Arc::clone(&log);
+    /// let balance = A::new(100).with_write_hook(move |v| audit.lock().unwrap().push(*v));
+    ///
+    /// balance.update(|b| *b -= 30);
+    /// balance.set(50);
+    /// assert_eq!(*log.lock().unwrap(), vec![70, 50]);
+    /// ```
+    #[cfg(feature = "observers")]
+    pub fn with_write_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&T) + Send + Sync + 'static,
+    {
+        self.extras.write_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Returns the label this value was built with, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::builder(0).label("jobs").build();
+    /// assert_eq!(data.label(), Some("jobs"));
+    /// assert_eq!(A::new(0).label(), None);
+    /// ```
+    #[cfg(feature = "labeled")]
+    pub fn label(&self) -> Option<&str> {
+        self.extras.label.as_deref()
+    }
+}
@@ -0,0 +1,44 @@
+//! Identity-based hashing and equality for `A<T>` handles.
+
+use crate::A;
+use std::hash::{Hash, Hasher};
+
+/// A wrapper that compares and hashes an [`A<T>`] by the address of its shared lock.
+///
+/// Two `ByAddress` values are equal exactly when they wrap clones of the same
+/// handle, regardless of the inner value. This allows keying a `HashMap` or
+/// `HashSet` by handle identity without requiring `T: Hash` or `T: Eq`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use tspawn::{A, ByAddress};
+///
+/// let data = A::new(vec![1, 2, 3]);
+/// let mut names = HashMap::new();
+/// names.insert(ByAddress(data.clone()), "numbers");
+///
+/// assert_eq!(names.get(&ByAddress(data)), Some(&"numbers"));
+/// ```
+pub struct ByAddress<T>(pub A<T>);
+
+impl<T> Clone for ByAddress<T> {
+    fn clone(&self) -> Self {
+        ByAddress(self.0.clone())
+    }
+}
+
+impl<T> PartialEq for ByAddress<T> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0.as_ptr(), other.0.as_ptr())
+    }
+}
+
+impl<T> Eq for ByAddress<T> {}
+
+impl<T> Hash for ByAddress<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_ptr().hash(state);
+    }
+}
@@ -0,0 +1,81 @@
+//! Interning of shared values by key.
+
+use crate::{WeakA, A};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cache that hands out one shared [`A<V>`] per key for as long as it is in use.
+///
+/// Entries are held as [`WeakA`] handles, so the cache never keeps a value
+/// alive on its own: once every handle returned for a key has been dropped, the
+/// next [`WeakCache::get_or_create`] for that key creates a fresh value. Dead
+/// entries are pruned whenever a value is created. Cloning a `WeakCache`
+/// yields another handle to the same cache.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::WeakCache;
+///
+/// let sessions = WeakCache::new();
+/// let first = sessions.get_or_create("ada", || vec!["login"]);
+/// let second = sessions.get_or_create("ada", || unreachable!());
+/// second.update(|events| events.push("view"));
+/// assert_eq!(first.get(), vec!["login", "view"]);
+/// ```
+pub struct WeakCache<K, V> {
+    entries: A<HashMap<K, WeakA<V>>>,
+}
+
+impl<K, V> Clone for WeakCache<K, V> {
+    fn clone(&self) -> Self {
+        WeakCache {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<K, V> Default for WeakCache<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> WeakCache<K, V> {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        WeakCache {
+            entries: A::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the number of entries, including any dead ones not yet pruned.
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    /// Returns `true` if the cache has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().is_empty()
+    }
+}
+
+impl<K: Eq + Hash, V> WeakCache<K, V> {
+    /// Returns the live handle for `key`, or creates one with `create`.
+    ///
+    /// The cache stays locked while `create` runs, so concurrent callers asking
+    /// for the same key always end up with the same handle.
+    pub fn get_or_create<F>(&self, key: K, create: F) -> A<V>
+    where
+        F: FnOnce() -> V,
+    {
+        let mut entries = self.entries.lock_write();
+        if let Some(handle) = entries.get(&key).and_then(WeakA::upgrade) {
+            return handle;
+        }
+        entries.retain(|_, entry| entry.is_alive());
+        let handle = A::new(create());
+        entries.insert(key, handle.downgrade());
+        handle
+    }
+}
@@ -0,0 +1,55 @@
+//! Saved copies of a shared value for undo.
+
+use crate::A;
+
+/// A copy of an [`A<T>`]'s value captured by [`A::checkpoint`].
+///
+/// Restoring a checkpoint with [`A::restore`] leaves it intact, so the same
+/// checkpoint can be restored any number of times. Keep several in a `Vec` for
+/// multi-level undo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint<T> {
+    value: T,
+}
+
+impl<T> Checkpoint<T> {
+    /// Returns the captured value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Consumes the checkpoint and returns the captured value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Clone> A<T> {
+    /// Captures a copy of the current value that can later be passed to [`A::restore`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let document = A::new(String::from("draft"));
+    /// let mut undo = vec![document.checkpoint()];
+    ///
+    /// document.update(|d| d.push_str(" v2"));
+    /// undo.push(document.checkpoint());
+    /// document.update(|d| d.clear());
+    ///
+    /// document.restore(&undo.pop().unwrap());
+    /// assert_eq!(document.get(), "draft v2");
+    /// document.restore(&undo.pop().unwrap());
+    /// assert_eq!(document.get(), "draft");
+    /// ```
+    pub fn checkpoint(&self) -> Checkpoint<T> {
+        Checkpoint { value: self.get() }
+    }
+
+    /// Replaces the value with a copy of the one captured in `checkpoint`.
+    pub fn restore(&self, checkpoint: &Checkpoint<T>) {
+        self.set(checkpoint.value.clone());
+    }
+}
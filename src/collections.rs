@@ -0,0 +1,615 @@
+//! Convenience methods for `A<T>` wrapping standard collections.
+
+use crate::{ReadGuard, A};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::RangeBounds;
+
+impl<T> A<Vec<T>> {
+    /// Returns the number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.lock_read().len()
+    }
+
+    /// Returns `true` if the vector has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.lock_read().is_empty()
+    }
+
+    /// Returns the number of elements the vector can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.lock_read().capacity()
+    }
+
+    /// Returns a short description of the vector's size for logging.
+    ///
+    /// The read lock is only tried, never waited for, so logging cannot stall
+    /// behind a writer; a contended lock is reported as `Vec(<locked>)`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(Vec::with_capacity(4));
+    /// data.update(|v| v.extend([1, 2, 3]));
+    /// assert_eq!(data.summary(), "Vec(len=3, cap=4)");
+    /// ```
+    pub fn summary(&self) -> String {
+        match self.try_lock_read() {
+            Some(vec) => format!("Vec(len={}, cap={})", vec.len(), vec.capacity()),
+            None => String::from("Vec(<locked>)"),
+        }
+    }
+
+    /// Clears the vector while keeping its allocated capacity.
+    ///
+    /// This method acquires a write lock and calls [`Vec::clear`], so the buffer
+    /// can be reused without reallocating, e.g. between benchmark iterations.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(Vec::with_capacity(16));
+    /// data.update(|v| v.extend([1, 2, 3]));
+    /// data.reset();
+    /// assert!(data.read().is_empty());
+    /// assert!(data.read().capacity() >= 16);
+    /// ```
+    pub fn reset(&self) {
+        let mut guard = self.lock_write();
+        guard.clear();
+        self.finish_write(guard);
+    }
+
+    /// Returns an owned read guard over the vector.
+    ///
+    /// This is [`A::read`] for vectors: the guard derefs to `Vec<T>` (and so to
+    /// `&[T]`) and can be moved into a spawned task, giving it zero-copy access
+    /// for as long as the guard is held.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let samples = A::new(vec![1, 2, 3]);
+    /// let slice = samples.read_slice();
+    /// assert_eq!(&slice[..], &[1, 2, 3]);
+    /// ```
+    pub fn read_slice(&self) -> ReadGuard<Vec<T>> {
+        self.read()
+    }
+
+    /// Runs `f` with the elements as a slice, under a read lock and without cloning.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let samples = A::new(vec![1, 2, 3]);
+    /// assert_eq!(samples.with_slice(|s| s.iter().sum::<i32>()), 6);
+    /// ```
+    pub fn with_slice<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&[T]) -> R,
+    {
+        self.run_labeled(|| f(&self.lock_read()))
+    }
+
+    /// Applies `f` to the element at `index` under a write lock.
+    ///
+    /// Returns `false` without calling `f` if `index` is out of bounds, so a
+    /// stale index never panics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let scores = A::new(vec![10, 20, 30]);
+    /// assert!(scores.update_at(1, |s| *s += 5));
+    /// assert!(!scores.update_at(7, |s| *s += 5));
+    /// assert_eq!(scores.get(), vec![10, 25, 30]);
+    /// ```
+    pub fn update_at<F>(&self, index: usize, f: F) -> bool
+    where
+        F: FnOnce(&mut T),
+    {
+        let mut guard = self.lock_write();
+        let Some(item) = guard.get_mut(index) else {
+            return false;
+        };
+        self.run_labeled(|| f(item));
+        self.finish_write(guard);
+        true
+    }
+
+    /// Keeps only the elements for which `pred` returns `true`, under one write lock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let ids = A::new(vec![1, 2, 3, 4]);
+    /// ids.retain(|id| id % 2 == 0);
+    /// assert_eq!(ids.get(), vec![2, 4]);
+    /// ```
+    pub fn retain<P>(&self, pred: P)
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let mut guard = self.lock_write();
+        self.run_labeled(|| guard.retain(pred));
+        self.finish_write(guard);
+    }
+
+    /// Sorts the vector under one write lock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let ids = A::new(vec![3, 1, 2]);
+    /// ids.sort();
+    /// assert_eq!(ids.get(), vec![1, 2, 3]);
+    /// ```
+    pub fn sort(&self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    /// Sorts the vector with a comparator under one write lock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let ids = A::new(vec![3, 1, 2]);
+    /// ids.sort_by(|a, b| b.cmp(a));
+    /// assert_eq!(ids.get(), vec![3, 2, 1]);
+    /// ```
+    pub fn sort_by<F>(&self, compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut guard = self.lock_write();
+        self.run_labeled(|| guard.sort_by(compare));
+        self.finish_write(guard);
+    }
+
+    /// Sorts the vector by a key extracted from each element, under one write lock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let words = A::new(vec!["ccc", "a", "bb"]);
+    /// words.sort_by_key(|w| w.len());
+    /// assert_eq!(words.get(), vec!["a", "bb", "ccc"]);
+    /// ```
+    pub fn sort_by_key<K, F>(&self, mut key: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| key(a).cmp(&key(b)));
+    }
+
+    /// Takes every element out of the vector, leaving it empty.
+    ///
+    /// The vector is swapped with an empty one under a single write lock, so the
+    /// returned elements keep the previous allocation and a concurrent producer's
+    /// push lands either in the returned batch or in the next one, never both.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let samples = A::new(vec![3, 1, 4]);
+    /// assert_eq!(samples.drain(), vec![3, 1, 4]);
+    /// assert!(samples.read().is_empty());
+    /// ```
+    pub fn drain(&self) -> Vec<T> {
+        let mut guard = self.lock_write();
+        let drained = std::mem::take(&mut *guard);
+        self.finish_write(guard);
+        drained
+    }
+
+    /// Removes and returns the leading elements for which `pred` returns `true`.
+    ///
+    /// Stops at the first element that fails the predicate, leaving it and
+    /// everything after it in place. The scan and removal happen under a single
+    /// write lock, so "consume everything that is ready" is atomic.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let queue = A::new(vec![1, 2, 7, 3]);
+    /// assert_eq!(queue.drain_while(|&n| n < 5), vec![1, 2]);
+    /// assert_eq!(queue.get(), vec![7, 3]);
+    /// ```
+    pub fn drain_while<P>(&self, pred: P) -> Vec<T>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut guard = self.lock_write();
+        let ready = self.run_labeled(|| guard.iter().take_while(|item| pred(item)).count());
+        let drained = guard.drain(..ready).collect();
+        self.finish_write(guard);
+        drained
+    }
+
+    /// Applies `f` to every element in parallel on the rayon thread pool.
+    ///
+    /// The write lock is held for the whole operation; only the per-element work
+    /// is parallelized. Only available with the `rayon` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let pixels = A::new(vec![10u8, 20, 30]);
+    /// pixels.par_update(|p| *p = 255 - *p);
+    /// assert_eq!(pixels.get(), vec![245, 235, 225]);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_update<F>(&self, f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) + Send + Sync,
+    {
+        use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+
+        let mut guard = self.lock_write();
+        self.run_labeled(|| guard.par_iter_mut().for_each(f));
+        self.finish_write(guard);
+    }
+
+    /// Maps every element and collects the results under a single read lock.
+    ///
+    /// The lock is released before returning, so the collection can be
+    /// processed further without blocking writers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let names = A::new(vec!["ada", "grace"]);
+    /// let upper: Vec<String> = names.map_collect(|n| n.to_uppercase());
+    /// assert_eq!(upper, vec!["ADA", "GRACE"]);
+    /// ```
+    pub fn map_collect<U, C, F>(&self, f: F) -> C
+    where
+        F: Fn(&T) -> U,
+        C: FromIterator<U>,
+    {
+        self.run_labeled(|| self.lock_read().iter().map(f).collect())
+    }
+}
+
+impl A<Vec<u8>> {
+    /// Copies the buffer into owned chunks of `chunk_size` bytes.
+    ///
+    /// The read lock is held only while copying, so the chunks can then be
+    /// written to a socket or file without blocking writers. The last chunk is
+    /// shorter if the length is not a multiple of `chunk_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let buffer = A::new(b"hello".to_vec());
+    /// assert_eq!(buffer.read_chunks(2), vec![b"he".to_vec(), b"ll".to_vec(), b"o".to_vec()]);
+    /// ```
+    pub fn read_chunks(&self, chunk_size: usize) -> Vec<Vec<u8>> {
+        self.value
+            .read()
+            .chunks(chunk_size)
+            .map(<[u8]>::to_vec)
+            .collect()
+    }
+}
+
+impl<T> A<VecDeque<T>> {
+    /// Appends an element to the back of the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::VecDeque;
+    /// use tspawn::A;
+    ///
+    /// let jobs = A::new(VecDeque::new());
+    /// jobs.push_back("build");
+    /// jobs.push_back("test");
+    /// assert_eq!(jobs.pop_front(), Some("build"));
+    /// ```
+    pub fn push_back(&self, value: T) {
+        let mut guard = self.lock_write();
+        guard.push_back(value);
+        self.finish_write(guard);
+    }
+
+    /// Removes and returns the element at the front of the queue, if any.
+    pub fn pop_front(&self) -> Option<T> {
+        let mut guard = self.lock_write();
+        let front = guard.pop_front();
+        self.finish_write(guard);
+        front
+    }
+}
+
+impl A<String> {
+    /// Clears the string while keeping its allocated capacity.
+    ///
+    /// This method acquires a write lock and calls [`String::clear`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(String::from("Hello"));
+    /// data.reset();
+    /// assert_eq!(data.get(), "");
+    /// ```
+    pub fn reset(&self) {
+        let mut guard = self.lock_write();
+        guard.clear();
+        self.finish_write(guard);
+    }
+}
+
+impl<K, V> A<HashMap<K, A<V>>>
+where
+    K: Eq + Hash,
+{
+    /// Builds a shared map whose values are each wrapped in their own `A`.
+    ///
+    /// Inner handles can be cloned out of the map and locked independently, so
+    /// updating one entry does not block readers of the map or of other entries.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let channels = A::nested_map([("logs", Vec::new()), ("messages", Vec::new())]);
+    /// let logs = channels.read()["logs"].clone();
+    /// logs.update(|l| l.push("started"));
+    /// assert_eq!(channels.read()["logs"].get(), vec!["started"]);
+    /// ```
+    pub fn nested_map<I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        A::new(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key, A::new(value)))
+                .collect(),
+        )
+    }
+}
+
+impl<K, V> A<HashMap<K, V>>
+where
+    K: Eq + Hash,
+{
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.lock_read().len()
+    }
+
+    /// Returns `true` if the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.lock_read().is_empty()
+    }
+
+    /// Returns the number of entries the map can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.lock_read().capacity()
+    }
+
+    /// Returns a short description of the map's size for logging.
+    ///
+    /// Like the `A<Vec<T>>` version, this never waits for the lock and reports
+    /// `HashMap(<locked>)` when it is contended.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(HashMap::from([("a", 1)]));
+    /// assert!(data.summary().starts_with("HashMap(len=1, cap="));
+    /// ```
+    pub fn summary(&self) -> String {
+        match self.try_lock_read() {
+            Some(map) => format!("HashMap(len={}, cap={})", map.len(), map.capacity()),
+            None => String::from("HashMap(<locked>)"),
+        }
+    }
+
+    /// Inserts `default()` for `key` if absent, then applies `update` to the entry.
+    ///
+    /// Both steps happen under a single write lock, so the get-or-insert-then-modify
+    /// sequence is atomic with respect to other handles.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use tspawn::A;
+    ///
+    /// let scores = A::new(HashMap::new());
+    /// scores.entry_update("alice", || 0, |score| *score += 10);
+    /// scores.entry_update("alice", || 0, |score| *score += 5);
+    /// assert_eq!(scores.read()["alice"], 15);
+    /// ```
+    pub fn entry_update<D, F>(&self, key: K, default: D, update: F)
+    where
+        D: FnOnce() -> V,
+        F: FnOnce(&mut V),
+    {
+        let mut guard = self.lock_write();
+        self.run_labeled(|| update(guard.entry(key).or_insert_with(default)));
+        self.finish_write(guard);
+    }
+
+    /// Keeps only the entries for which `pred` returns `true`, under one write lock.
+    ///
+    /// `pred` may also modify the values it keeps.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use tspawn::A;
+    ///
+    /// let sessions = A::new(HashMap::from([("a", 0), ("b", 5)]));
+    /// sessions.retain(|_, idle| {
+    ///     *idle += 1;
+    ///     *idle < 5
+    /// });
+    /// assert_eq!(sessions.read().get("a"), Some(&1));
+    /// assert!(!sessions.read().contains_key("b"));
+    /// ```
+    pub fn retain<P>(&self, pred: P)
+    where
+        P: FnMut(&K, &mut V) -> bool,
+    {
+        let mut guard = self.lock_write();
+        self.run_labeled(|| guard.retain(pred));
+        self.finish_write(guard);
+    }
+}
+
+impl<T> A<HashSet<T>>
+where
+    T: Eq + Hash,
+{
+    /// Inserts `value`, returning `true` if it was not already present.
+    ///
+    /// The membership check and the insert happen under one write lock, so
+    /// exactly one of several concurrent callers with the same value sees `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    /// use tspawn::A;
+    ///
+    /// let seen = A::new(HashSet::new());
+    /// assert!(seen.insert_new("msg-1"));
+    /// assert!(!seen.insert_new("msg-1"));
+    /// ```
+    pub fn insert_new(&self, value: T) -> bool {
+        let mut guard = self.lock_write();
+        let inserted = guard.insert(value);
+        self.finish_write(guard);
+        inserted
+    }
+}
+
+impl<K, V> A<BTreeMap<K, V>>
+where
+    K: Ord,
+{
+    /// Inserts a key-value pair, returning the previous value for `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use tspawn::A;
+    ///
+    /// let prices = A::new(BTreeMap::new());
+    /// assert_eq!(prices.insert("apple", 3), None);
+    /// assert_eq!(prices.insert("apple", 4), Some(3));
+    /// ```
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let mut guard = self.lock_write();
+        let previous = guard.insert(key, value);
+        self.finish_write(guard);
+        previous
+    }
+
+    /// Removes `key`, returning its value if it was present.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let mut guard = self.lock_write();
+        let removed = guard.remove(key);
+        self.finish_write(guard);
+        removed
+    }
+
+    /// Returns a clone of the value for `key`.
+    pub fn get_cloned(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.lock_read().get(key).cloned()
+    }
+
+    /// Returns clones of the entries whose keys fall in `range`, in key order.
+    ///
+    /// The read lock is held only while copying, so the result can be processed
+    /// without blocking writers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use tspawn::A;
+    ///
+    /// let events = A::new(BTreeMap::from([(10, "boot"), (20, "login"), (30, "logout")]));
+    /// assert_eq!(events.range_cloned(15..), vec![(20, "login"), (30, "logout")]);
+    /// ```
+    pub fn range_cloned<R>(&self, range: R) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+        R: RangeBounds<K>,
+    {
+        self.value
+            .read()
+            .range(range)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Returns a clone of the entry with the smallest key.
+    pub fn first_key_value_cloned(&self) -> Option<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.value
+            .read()
+            .first_key_value()
+            .map(|(key, value)| (key.clone(), value.clone()))
+    }
+}
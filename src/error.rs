@@ -0,0 +1,15 @@
+//! Error types returned by `A<T>` operations.
+
+use std::fmt;
+
+/// Error returned when a lock could not be acquired within the given timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut;
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("timed out waiting for the lock")
+    }
+}
+
+impl std::error::Error for TimedOut {}
@@ -0,0 +1,91 @@
+//! Registries of `A<T>` handles that can be updated together.
+
+use crate::A;
+
+/// A shared registry of [`A<T>`] handles, for updating many values at once.
+///
+/// Cloning a `Group` yields another handle to the same registry. Registered
+/// handles are kept alive by the group; use a `A<Vec<WeakA<T>>>` registry
+/// instead when members should drop out on their own.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{Group, A};
+///
+/// let clients = Group::new();
+/// let alice = A::new(0);
+/// let bob = A::new(10);
+/// clients.register(alice.clone());
+/// clients.register(bob.clone());
+///
+/// clients.broadcast(|score| *score += 1);
+/// assert_eq!(clients.snapshot_all(), vec![1, 11]);
+/// ```
+pub struct Group<T> {
+    members: A<Vec<A<T>>>,
+}
+
+impl<T> Clone for Group<T> {
+    fn clone(&self) -> Self {
+        Group {
+            members: self.members.clone(),
+        }
+    }
+}
+
+impl<T> Default for Group<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Group<T> {
+    /// Creates an empty group.
+    pub fn new() -> Self {
+        Group {
+            members: A::new(Vec::new()),
+        }
+    }
+
+    /// Adds a handle to the group.
+    pub fn register(&self, handle: A<T>) {
+        self.members.update(|members| members.push(handle));
+    }
+
+    /// Returns the number of registered handles.
+    pub fn len(&self) -> usize {
+        self.members.read().len()
+    }
+
+    /// Returns `true` if no handles are registered.
+    pub fn is_empty(&self) -> bool {
+        self.members.read().is_empty()
+    }
+
+    /// Applies `f` to every registered value, in registration order.
+    ///
+    /// Each value is updated under its own write lock, one at a time. The
+    /// registry itself is not locked while `f` runs, so `f` may register
+    /// further handles; those are not visited by this call.
+    pub fn broadcast<F>(&self, f: F)
+    where
+        F: Fn(&mut T),
+    {
+        for member in self.members() {
+            member.update(&f);
+        }
+    }
+
+    /// Returns a clone of every registered value, in registration order.
+    pub fn snapshot_all(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.members().iter().map(A::get).collect()
+    }
+
+    fn members(&self) -> Vec<A<T>> {
+        self.members.read().clone()
+    }
+}
@@ -0,0 +1,257 @@
+//! Owned guards handed out by [`A`].
+
+#[cfg(any(feature = "await_check", feature = "lock_order"))]
+use crate::held::Held;
+use crate::A;
+use parking_lot::{ArcRwLockReadGuard, ArcRwLockWriteGuard, MappedRwLockWriteGuard, RawRwLock};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+#[cfg(feature = "lock_owner_tracking")]
+use std::{panic::Location, sync::Arc, thread::ThreadId};
+
+#[cfg(feature = "lock_owner_tracking")]
+type Writer = parking_lot::Mutex<Option<(ThreadId, &'static Location<'static>)>>;
+
+/// An owned read guard for the value behind an [`A<T>`].
+///
+/// Returned by [`A::read`] and the other owning read accessors. The guard is
+/// `'static`, so it can be moved into a spawned task, and releases the read
+/// lock when dropped. With the `lock_order` feature the handle's level counts
+/// as held for as long as the guard is alive.
+pub struct ReadGuard<T> {
+    guard: ArcRwLockReadGuard<RawRwLock, T>,
+    #[cfg(any(feature = "await_check", feature = "lock_order"))]
+    pub(crate) held: Option<Held>,
+}
+
+impl<T> ReadGuard<T> {
+    pub(crate) fn new(handle: &A<T>, guard: ArcRwLockReadGuard<RawRwLock, T>) -> Self {
+        #[cfg(not(any(feature = "await_check", feature = "lock_order")))]
+        let _ = handle;
+        ReadGuard {
+            guard,
+            #[cfg(any(feature = "await_check", feature = "lock_order"))]
+            held: handle.track(),
+        }
+    }
+}
+
+impl<T> Deref for ReadGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ReadGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for ReadGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+/// An owned write guard for the value behind an [`A<T>`].
+///
+/// Returned by [`A::write`] and the other owning write accessors. The guard is
+/// `'static`, so it can be moved into a spawned task, and releases the write
+/// lock when dropped. Writes made through it are not published to
+/// [`A::changes`] subscribers or write hooks. With the `lock_owner_tracking`
+/// feature the recorded owner is cleared when the guard is dropped.
+pub struct WriteGuard<T> {
+    guard: ArcRwLockWriteGuard<RawRwLock, T>,
+    #[cfg(any(feature = "await_check", feature = "lock_order"))]
+    pub(crate) held: Option<Held>,
+    #[cfg(feature = "lock_owner_tracking")]
+    writer: Arc<Writer>,
+}
+
+impl<T> WriteGuard<T> {
+    pub(crate) fn new(handle: &A<T>, guard: ArcRwLockWriteGuard<RawRwLock, T>) -> Self {
+        #[cfg(not(any(
+            feature = "await_check",
+            feature = "lock_order",
+            feature = "lock_owner_tracking"
+        )))]
+        let _ = handle;
+        WriteGuard {
+            guard,
+            #[cfg(any(feature = "await_check", feature = "lock_order"))]
+            held: handle.track(),
+            #[cfg(feature = "lock_owner_tracking")]
+            writer: Arc::clone(&handle.extras.writer),
+        }
+    }
+}
+
+impl<T> Deref for WriteGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for WriteGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for WriteGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for WriteGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+#[cfg(feature = "lock_owner_tracking")]
+impl<T> Drop for WriteGuard<T> {
+    fn drop(&mut self) {
+        // Runs before the lock itself is released, so no later owner is cleared.
+        *self.writer.lock() = None;
+    }
+}
+
+/// A write guard scoped to a part of the value behind an [`A<T>`].
+///
+/// Returned by [`A::write_variant`]. It borrows the handle and releases the
+/// write lock when dropped, handing it off fairly if the handle was built with
+/// [`ABuilder::fair`]. Like [`WriteGuard`], writes made through it are not
+/// published to [`A::changes`] subscribers or write hooks, and with the
+/// `lock_owner_tracking` feature the recorded owner is cleared on drop.
+///
+/// [`ABuilder::fair`]: crate::ABuilder::fair
+pub struct MappedWriteGuard<'a, U: ?Sized> {
+    guard: Option<Locked<MappedRwLockWriteGuard<'a, U>>>,
+    fair: bool,
+    #[cfg(feature = "lock_owner_tracking")]
+    writer: &'a Writer,
+}
+
+impl<'a, U: ?Sized> MappedWriteGuard<'a, U> {
+    pub(crate) fn new<T>(handle: &'a A<T>, guard: Locked<MappedRwLockWriteGuard<'a, U>>) -> Self {
+        MappedWriteGuard {
+            guard: Some(guard),
+            fair: handle.extras.fair,
+            #[cfg(feature = "lock_owner_tracking")]
+            writer: &handle.extras.writer,
+        }
+    }
+}
+
+impl<U: ?Sized> Deref for MappedWriteGuard<'_, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        self.guard.as_deref().expect("guard is only taken on drop")
+    }
+}
+
+impl<U: ?Sized> DerefMut for MappedWriteGuard<'_, U> {
+    fn deref_mut(&mut self) -> &mut U {
+        self.guard
+            .as_deref_mut()
+            .expect("guard is only taken on drop")
+    }
+}
+
+impl<U: ?Sized + fmt::Debug> fmt::Debug for MappedWriteGuard<'_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<U: ?Sized> Drop for MappedWriteGuard<'_, U> {
+    fn drop(&mut self) {
+        #[cfg(feature = "lock_owner_tracking")]
+        {
+            *self.writer.lock() = None;
+        }
+        if let Some(guard) = self.guard.take() {
+            if self.fair {
+                MappedRwLockWriteGuard::unlock_fair(guard.into_inner());
+            }
+        }
+    }
+}
+
+/// A borrowed guard taken by the `A<T>` helpers.
+///
+/// With the `lock_order` feature it keeps the handle's level held for as long
+/// as the helper holds the lock, so closures run under it are checked too.
+pub struct Locked<G> {
+    guard: G,
+    #[cfg(feature = "lock_order")]
+    _held: Option<Held>,
+}
+
+impl<G> Locked<G> {
+    pub(crate) fn new<T>(handle: &A<T>, guard: G) -> Self {
+        #[cfg(not(feature = "lock_order"))]
+        let _ = handle;
+        Locked {
+            guard,
+            #[cfg(feature = "lock_order")]
+            _held: handle.level_token(),
+        }
+    }
+
+    /// Converts the inner guard, keeping the level held.
+    pub(crate) fn map<H>(self, f: impl FnOnce(G) -> H) -> Locked<H> {
+        Locked {
+            guard: f(self.guard),
+            #[cfg(feature = "lock_order")]
+            _held: self._held,
+        }
+    }
+
+    /// Converts the inner guard with a fallible mapping, keeping the level held
+    /// either way.
+    pub(crate) fn try_map<H, E>(
+        self,
+        f: impl FnOnce(G) -> Result<H, E>,
+    ) -> Result<Locked<H>, Locked<E>> {
+        match f(self.guard) {
+            Ok(guard) => Ok(Locked {
+                guard,
+                #[cfg(feature = "lock_order")]
+                _held: self._held,
+            }),
+            Err(guard) => Err(Locked {
+                guard,
+                #[cfg(feature = "lock_order")]
+                _held: self._held,
+            }),
+        }
+    }
+
+    /// Returns the inner guard, no longer holding the level.
+    pub(crate) fn into_inner(self) -> G {
+        self.guard
+    }
+}
+
+impl<G: Deref> Deref for Locked<G> {
+    type Target = G::Target;
+
+    fn deref(&self) -> &G::Target {
+        &self.guard
+    }
+}
+
+impl<G: DerefMut> DerefMut for Locked<G> {
+    fn deref_mut(&mut self) -> &mut G::Target {
+        &mut self.guard
+    }
+}
@@ -0,0 +1,220 @@
+//! Tracking of guards that are still held, for the `lock_order` and
+//! `await_check` features.
+//!
+//! A tracked guard carries a [`Held`] token that is marked released when the
+//! guard is dropped, on whichever thread that happens. Tokens with a lock level
+//! are also pushed onto a thread-local stack on the thread that acquired them,
+//! which `lock_order` consults before each acquisition.
+//!
+//! Guards captured by the `tspawn!` family are registered with [`register`] as
+//! they are taken. The [`Tracked`] wrapper around the spawned task claims them:
+//! it moves their levels off the spawning thread and onto whichever thread is
+//! polling the task, and with `await_check` reports captures still held the
+//! first time the task returns `Pending`. A guard cannot be reacquired once
+//! dropped, so that first suspension settles every capture.
+//!
+//! Only captures are checked for `await_check`. A [`ReadGuard`] or
+//! [`WriteGuard`] taken by hand inside a task and held across an `.await` is
+//! not reported, since nothing ties it to the task that holds it.
+
+use crate::{ReadGuard, WriteGuard, A};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+struct Entry {
+    level: Option<u32>,
+    released: AtomicBool,
+}
+
+impl Entry {
+    fn is_released(&self) -> bool {
+        self.released.load(Ordering::Acquire)
+    }
+}
+
+thread_local! {
+    static STACK: RefCell<Vec<Arc<Entry>>> = const { RefCell::new(Vec::new()) };
+    static PENDING: RefCell<Vec<Capture>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Names of captures reported so far, for tests.
+#[cfg(all(test, feature = "await_check"))]
+pub(crate) static REPORTED: parking_lot::Mutex<Vec<&'static str>> =
+    parking_lot::Mutex::new(Vec::new());
+
+/// Marks a lock as held until dropped.
+pub struct Held(Arc<Entry>);
+
+impl Held {
+    /// Creates a token for a lock just acquired by the current thread.
+    fn new(level: Option<u32>) -> Self {
+        let entry = Arc::new(Entry {
+            level,
+            released: AtomicBool::new(false),
+        });
+        if level.is_some() {
+            STACK.with(|stack| stack.borrow_mut().push(Arc::clone(&entry)));
+        }
+        Held(entry)
+    }
+}
+
+impl Drop for Held {
+    fn drop(&mut self) {
+        self.0.released.store(true, Ordering::Release);
+    }
+}
+
+/// Returns the highest level held by the current thread, if any.
+#[cfg(feature = "lock_order")]
+pub(crate) fn highest_level() -> Option<u32> {
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        stack.retain(|entry| !entry.is_released());
+        stack.iter().filter_map(|entry| entry.level).max()
+    })
+}
+
+impl<T> A<T> {
+    /// Returns a token tracking a guard on this handle's lock just acquired by
+    /// the current thread, or `None` if nothing needs to know when it is dropped.
+    pub(crate) fn track(&self) -> Option<Held> {
+        if !cfg!(debug_assertions) {
+            return None;
+        }
+        #[cfg(feature = "lock_order")]
+        let level = self.level();
+        #[cfg(not(feature = "lock_order"))]
+        let level = None;
+        if level.is_none() && !cfg!(feature = "await_check") {
+            return None;
+        }
+        Some(Held::new(level))
+    }
+}
+
+/// A guard type that a macro capture can produce.
+pub trait Guard {
+    /// Returns the token tracking this guard, if it is tracked.
+    fn held(&self) -> Option<&Held>;
+}
+
+impl<T> Guard for ReadGuard<T> {
+    fn held(&self) -> Option<&Held> {
+        self.held.as_ref()
+    }
+}
+
+impl<T> Guard for WriteGuard<T> {
+    fn held(&self) -> Option<&Held> {
+        self.held.as_ref()
+    }
+}
+
+impl<G: Guard> Guard for Option<G> {
+    fn held(&self) -> Option<&Held> {
+        self.as_ref().and_then(Guard::held)
+    }
+}
+
+/// A guard captured by the task about to be spawned.
+struct Capture {
+    #[cfg(feature = "await_check")]
+    name: &'static str,
+    entry: Arc<Entry>,
+}
+
+/// Forgets captures left behind by a spawn that never reached [`Tracked::new`].
+pub fn begin() {
+    PENDING.with(|pending| pending.borrow_mut().clear());
+}
+
+/// Records `guard` as a capture of the task about to be spawned and returns it.
+#[cfg_attr(not(feature = "await_check"), allow(unused_variables))]
+pub fn register<G: Guard>(name: &'static str, guard: G) -> G {
+    if let Some(held) = guard.held() {
+        let capture = Capture {
+            #[cfg(feature = "await_check")]
+            name,
+            entry: Arc::clone(&held.0),
+        };
+        PENDING.with(|pending| pending.borrow_mut().push(capture));
+    }
+    guard
+}
+
+/// Future wrapper that tracks the guards captured by a spawned task.
+pub struct Tracked<F> {
+    future: Pin<Box<F>>,
+    captures: Vec<Capture>,
+    #[cfg(feature = "await_check")]
+    suspended: bool,
+}
+
+impl<F> Tracked<F> {
+    pub fn new(future: F) -> Self {
+        let captures = PENDING.with(|pending| pending.take());
+        // The captures now belong to the task, not to the spawning thread.
+        STACK.with(|stack| {
+            stack.borrow_mut().retain(|entry| {
+                !captures
+                    .iter()
+                    .any(|capture| Arc::ptr_eq(&capture.entry, entry))
+            })
+        });
+        Tracked {
+            future: Box::pin(future),
+            captures,
+            #[cfg(feature = "await_check")]
+            suspended: false,
+        }
+    }
+}
+
+impl<F: Future> Future for Tracked<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        self.captures.retain(|capture| !capture.entry.is_released());
+        let levels = self
+            .captures
+            .iter()
+            .filter(|capture| capture.entry.level.is_some())
+            .map(|capture| Arc::clone(&capture.entry));
+        let pushed = STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let before = stack.len();
+            stack.extend(levels);
+            stack.len() - before
+        });
+
+        let poll = self.future.as_mut().poll(cx);
+
+        if pushed > 0 {
+            STACK.with(|stack| {
+                stack.borrow_mut().retain(|entry| {
+                    !self
+                        .captures
+                        .iter()
+                        .any(|capture| Arc::ptr_eq(&capture.entry, entry))
+                })
+            });
+        }
+        #[cfg(feature = "await_check")]
+        if poll.is_pending() && !self.suspended {
+            self.suspended = true;
+            for capture in &self.captures {
+                if !capture.entry.is_released() {
+                    #[cfg(test)]
+                    REPORTED.lock().push(capture.name);
+                    tracing::warn!(capture = capture.name, "tspawn guard held across an .await");
+                }
+            }
+        }
+        poll
+    }
+}
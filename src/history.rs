@@ -0,0 +1,103 @@
+//! Shared values that remember their most recent states.
+
+use crate::A;
+use std::collections::VecDeque;
+
+/// An [`A<T>`] that keeps a ring buffer of the last values written to it.
+///
+/// Writes made through [`HistoryA::set`], [`HistoryA::update`] and
+/// [`HistoryA::replace`] record a clone of the new value, keeping only the most
+/// recent `capacity` entries. Clones share both the value and its history.
+/// Only available with the `history` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::HistoryA;
+///
+/// let state = HistoryA::new("idle", 2);
+/// state.set("loading");
+/// state.set("ready");
+/// state.set("error");
+/// assert_eq!(state.history(), vec!["ready", "error"]);
+/// ```
+pub struct HistoryA<T> {
+    value: A<T>,
+    history: A<VecDeque<T>>,
+    capacity: usize,
+}
+
+impl<T> Clone for HistoryA<T> {
+    fn clone(&self) -> Self {
+        HistoryA {
+            value: self.value.clone(),
+            history: self.history.clone(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+impl<T: Clone> HistoryA<T> {
+    /// Creates a new value that retains up to `capacity` past writes.
+    ///
+    /// The initial value is not recorded.
+    pub fn new(value: T, capacity: usize) -> Self {
+        HistoryA {
+            value: A::new(value),
+            history: A::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Returns a clone of the current value.
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+
+    /// Sets the value and records it.
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+
+    /// Updates the value with a closure and records the result.
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        let mut guard = self.value.lock_write();
+        f(&mut guard);
+        // Record while still holding the value lock so the history order
+        // matches the order in which writes were applied.
+        self.record(guard.clone());
+        self.value.finish_write(guard);
+    }
+
+    /// Replaces the value, records the new one, and returns the previous value.
+    pub fn replace(&self, value: T) -> T {
+        let mut guard = self.value.lock_write();
+        let old = std::mem::replace(&mut *guard, value);
+        self.record(guard.clone());
+        self.value.finish_write(guard);
+        old
+    }
+
+    /// Returns the recorded values, oldest first.
+    pub fn history(&self) -> Vec<T> {
+        self.history.read().iter().cloned().collect()
+    }
+
+    /// Returns the underlying handle.
+    ///
+    /// Writes made directly through this handle are not recorded.
+    pub fn handle(&self) -> &A<T> {
+        &self.value
+    }
+
+    fn record(&self, value: T) {
+        let mut history = self.history.lock_write();
+        history.push_back(value);
+        while history.len() > self.capacity {
+            history.pop_front();
+        }
+    }
+}
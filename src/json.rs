@@ -0,0 +1,91 @@
+//! JSON Pointer access for `A<serde_json::Value>` and JSON rendering for logs.
+
+use crate::A;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// Renders a shared value as JSON when formatted. Created by [`A::log_value`].
+struct LogValue<'a, T> {
+    handle: &'a A<T>,
+}
+
+impl<T: Serialize> fmt::Display for LogValue<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(guard) = self.handle.try_lock_read() else {
+            return f.write_str("<locked>");
+        };
+        match serde_json::to_string(&*guard) {
+            Ok(json) => f.write_str(&json),
+            Err(error) => write!(f, "<unserializable: {error}>"),
+        }
+    }
+}
+
+impl<T: Serialize> A<T> {
+    /// Returns a wrapper that renders the value as JSON only when it is formatted.
+    ///
+    /// Nothing is locked or serialized until the wrapper is displayed, so passing
+    /// it to a log macro costs nothing when the line is filtered out. Formatting
+    /// takes the read lock without blocking and renders `<locked>` if a writer
+    /// holds it. Only available with the `serde` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let retries = A::new(vec![1, 2]);
+    /// assert_eq!(format!("state={}", retries.log_value()), "state=[1,2]");
+    /// ```
+    pub fn log_value(&self) -> impl fmt::Display + '_ {
+        LogValue { handle: self }
+    }
+}
+
+impl A<Value> {
+    /// Returns a clone of the value at `pointer`, using JSON Pointer syntax.
+    ///
+    /// Returns `None` if nothing exists at that path. See
+    /// [`Value::pointer`] for the pointer format.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    /// use tspawn::A;
+    ///
+    /// let config = A::new(json!({ "server": { "port": 8080 } }));
+    /// assert_eq!(config.json_get("/server/port"), Some(json!(8080)));
+    /// assert_eq!(config.json_get("/server/host"), None);
+    /// ```
+    pub fn json_get(&self, pointer: &str) -> Option<Value> {
+        self.lock_read().pointer(pointer).cloned()
+    }
+
+    /// Replaces the value at `pointer`, using JSON Pointer syntax.
+    ///
+    /// Returns `true` if the path existed and was updated. Missing paths are not
+    /// created, so `false` means the document was left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    /// use tspawn::A;
+    ///
+    /// let config = A::new(json!({ "server": { "port": 8080 } }));
+    /// assert!(config.json_set("/server/port", json!(9090)));
+    /// assert!(!config.json_set("/client/port", json!(1)));
+    /// assert_eq!(config.json_get("/server/port"), Some(json!(9090)));
+    /// ```
+    pub fn json_set(&self, pointer: &str, value: Value) -> bool {
+        let mut guard = self.lock_write();
+        match guard.pointer_mut(pointer) {
+            Some(slot) => *slot = value,
+            None => return false,
+        }
+        self.finish_write(guard);
+        true
+    }
+}
@@ -0,0 +1,164 @@
+//! Handle-count tracking for catching leaked `A<T>` clones and coordinating teardown.
+
+use crate::A;
+use parking_lot::RwLock;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+/// An RAII guard that asserts the number of handles returned to its starting value.
+///
+/// Created with [`A::leak_guard`]. The guard records the strong handle count at
+/// creation time without holding a handle itself, and panics on drop if the count
+/// differs, making handle-leak regressions fail loudly. No check is made if the
+/// thread is already panicking.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::A;
+///
+/// let data = A::new(0);
+/// let guard = data.leak_guard();
+/// {
+///     let clone = data.clone();
+///     clone.set(1);
+/// }
+/// drop(guard); // Passes: the clone was dropped
+/// ```
+pub struct LeakGuard<T> {
+    value: Weak<RwLock<T>>,
+    expected: usize,
+}
+
+impl<T> Drop for LeakGuard<T> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        let actual = self.value.strong_count();
+        assert_eq!(
+            actual, self.expected,
+            "LeakGuard: expected {} handle(s) on drop, found {}",
+            self.expected, actual
+        );
+    }
+}
+
+impl<T> A<T> {
+    /// Returns the number of handles currently sharing this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// let cloned = data.clone();
+    /// assert_eq!(data.handle_count(), 2);
+    /// drop(cloned);
+    /// assert_eq!(data.handle_count(), 1);
+    /// ```
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.value)
+    }
+
+    /// Panics if this is not the only handle to the shared value.
+    ///
+    /// Useful in tests to assert that no spawned task kept a clone alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any other clone of this handle still exists.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// data.assert_unique();
+    /// ```
+    #[track_caller]
+    pub fn assert_unique(&self) {
+        let count = self.handle_count();
+        assert!(
+            count == 1,
+            "expected a unique handle, found {} handles",
+            count
+        );
+    }
+
+    /// Returns a [`LeakGuard`] that asserts the handle count is unchanged when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(0);
+    /// let _guard = data.leak_guard();
+    /// let clone = data.clone();
+    /// drop(clone);
+    /// ```
+    pub fn leak_guard(&self) -> LeakGuard<T> {
+        LeakGuard {
+            value: Arc::downgrade(&self.value),
+            expected: self.handle_count(),
+        }
+    }
+
+    /// Blocks the current thread until this is the only handle to the value.
+    ///
+    /// The handle count is checked every `poll` interval. Once this returns,
+    /// [`A::try_unwrap`] succeeds unless a new clone is made in the meantime. This
+    /// hangs forever if another handle leaks, and must not be called from within
+    /// an async task; use [`A::wait_until_unique_async`] there.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(42);
+    /// let worker = {
+    ///     let data = data.clone();
+    ///     std::thread::spawn(move || data.set(7))
+    /// };
+    ///
+    /// data.wait_until_unique(Duration::from_millis(1));
+    /// assert_eq!(data.try_unwrap().ok(), Some(7));
+    /// # worker.join().unwrap();
+    /// ```
+    pub fn wait_until_unique(&self, poll: Duration) {
+        while self.handle_count() > 1 {
+            std::thread::sleep(poll);
+        }
+    }
+
+    /// Waits, yielding to the async runtime, until this is the only handle to the value.
+    ///
+    /// This is the async counterpart of [`A::wait_until_unique`]: instead of
+    /// sleeping it repeatedly yields to the scheduler, so other tasks can finish
+    /// and drop their clones. It never completes if another handle leaks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::{A, tspawn};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let data = A::new(1);
+    /// let _task = tspawn!(mut data, { *data += 1; });
+    ///
+    /// data.wait_until_unique_async().await;
+    /// assert_eq!(data.try_unwrap().ok(), Some(2));
+    /// # }
+    /// ```
+    pub async fn wait_until_unique_async(&self) {
+        while self.handle_count() > 1 {
+            tokio::task::yield_now().await;
+        }
+    }
+}
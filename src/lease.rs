@@ -0,0 +1,87 @@
+//! Read guards that report their acquisition and release for auditing.
+
+use crate::{ReadGuard, A};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_LEASE: AtomicU64 = AtomicU64::new(1);
+
+/// An event reported by a [`LeasedRead`] guard to its audit callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaseEvent {
+    /// The read lock was acquired under the given lease id.
+    Acquired(u64),
+    /// The guard with the given lease id was dropped and the lock released.
+    Released(u64),
+}
+
+/// A read guard tagged with a unique lease id.
+///
+/// Created by [`A::read_leased`]. The guard reports [`LeaseEvent::Acquired`]
+/// when it is created and [`LeaseEvent::Released`] after the lock is released,
+/// which makes it possible to audit or meter who holds a value and for how long.
+pub struct LeasedRead<T> {
+    guard: Option<ReadGuard<T>>,
+    lease: u64,
+    audit: Box<dyn Fn(LeaseEvent) + Send + Sync>,
+}
+
+impl<T> LeasedRead<T> {
+    /// Returns the lease id, unique for the lifetime of the process.
+    pub fn lease_id(&self) -> u64 {
+        self.lease
+    }
+}
+
+impl<T> Deref for LeasedRead<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.as_ref().expect("guard is only taken on drop")
+    }
+}
+
+impl<T> Drop for LeasedRead<T> {
+    fn drop(&mut self) {
+        drop(self.guard.take());
+        (self.audit)(LeaseEvent::Released(self.lease));
+    }
+}
+
+impl<T> A<T> {
+    /// Acquires a read lock wrapped in a [`LeasedRead`] guard that reports to `audit`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::{Arc, Mutex};
+    /// use tspawn::{LeaseEvent, A};
+    ///
+    /// let events = Arc::new(Mutex::new(Vec::new()));
+    /// let log = Arc::clone(&events);
+    ///
+    /// let data = A::new(42);
+    /// let guard = data.read_leased(move |event| log.lock().unwrap().push(event));
+    /// let lease = guard.lease_id();
+    /// assert_eq!(*guard, 42);
+    /// drop(guard);
+    ///
+    /// assert_eq!(
+    ///     *events.lock().unwrap(),
+    ///     vec![LeaseEvent::Acquired(lease), LeaseEvent::Released(lease)]
+    /// );
+    /// ```
+    pub fn read_leased<F>(&self, audit: F) -> LeasedRead<T>
+    where
+        F: Fn(LeaseEvent) + Send + Sync + 'static,
+    {
+        let guard = self.read();
+        let lease = NEXT_LEASE.fetch_add(1, Ordering::Relaxed);
+        audit(LeaseEvent::Acquired(lease));
+        LeasedRead {
+            guard: Some(guard),
+            lease,
+            audit: Box::new(audit),
+        }
+    }
+}
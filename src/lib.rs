@@ -53,6 +53,7 @@
 //! // Read access
 //! let value = data.get(); // Returns a clone of the inner value
 //! let guard = data.read(); // Returns a read guard
+//! drop(guard); // Release the read lock before writing
 //!
 //! // Write access
 //! data.set(100); // Set a new value
@@ -82,8 +83,71 @@
 //! # }
 //! ```
 
+// Lets code generated by `tspawn-derive` refer to `::tspawn` inside this crate.
+#[cfg(feature = "derive")]
+extern crate self as tspawn;
+
 mod a;
+mod abort;
+mod any;
+mod batch;
+#[cfg(feature = "bincode")]
+mod binary;
+mod builder;
+mod by_address;
+mod cache;
+mod checkpoint;
+mod collections;
+mod error;
+mod group;
+mod guard;
+#[cfg(any(feature = "await_check", feature = "lock_order"))]
+mod held;
+#[cfg(feature = "history")]
+mod history;
+#[cfg(feature = "serde")]
+mod json;
+mod leak;
+mod lease;
+#[cfg(feature = "lock_order")]
+mod lock_order;
+mod lock_state;
+mod ordered;
+mod phase;
+mod pipeline;
+mod priority;
+mod spawn;
+#[cfg(feature = "arc_swap")]
+mod swappable;
+mod task_body;
+mod weak;
 pub use a::A;
+pub use abort::AbortOnDrop;
+pub use any::AnyA;
+pub use batch::UpdateBatch;
+pub use builder::ABuilder;
+pub use by_address::ByAddress;
+pub use cache::WeakCache;
+pub use checkpoint::Checkpoint;
+pub use error::TimedOut;
+pub use group::Group;
+pub use guard::{MappedWriteGuard, ReadGuard, WriteGuard};
+#[cfg(feature = "history")]
+pub use history::HistoryA;
+pub use leak::LeakGuard;
+pub use lease::{LeaseEvent, LeasedRead};
+pub use lock_state::LockState;
+pub use ordered::OrderedTasks;
+pub use phase::PhaseGroup;
+pub use pipeline::Pipeline;
+pub use priority::PriorityA;
+pub use spawn::{spawn_send, BoundedUpdater, QuerySender, WriteClient};
+#[cfg(feature = "arc_swap")]
+pub use swappable::{ArcSwapA, SwappableA};
+pub use weak::WeakA;
+
+#[cfg(feature = "derive")]
+pub use tspawn_derive::Shared;
 
 // Macro to automatically clone variables and spawn a tokio task
 /// Spawns a tokio task with automatic cloning and lock management for shared state.
@@ -97,6 +161,10 @@ pub use a::A;
 /// - `var` - Clones the wrapper into the task (no automatic locking)
 /// - `ref var` - Provides read-only access (automatically acquires read lock)
 /// - `mut var` - Provides write access (automatically acquires write lock)
+/// - `read var` / `write var` - Explicit aliases of `ref var` / `mut var`
+/// - `try_read var` / `try_write var` - Acquire the lock without blocking when the
+///   task is spawned; if it is contended the body is skipped and the task does
+///   nothing. Bodies using these modifiers must evaluate to `()`.
 ///
 /// # Expansion Pattern
 ///
@@ -104,7 +172,7 @@ pub use a::A;
 /// ```rust,ignore
 /// {
 ///     let var = ::core::clone::Clone::clone(&var);
-///     tokio::spawn({
+///     tspawn::spawn_send({
 ///         let mut var = var.write(); // or let var = var.read(); for ref
 ///         async move {
 ///             // user code here
@@ -168,8 +236,10 @@ pub use a::A;
 ///
 /// # Returns
 ///
-/// Returns a `JoinHandle<()>` from `tokio::spawn`, which can be awaited to
-/// ensure the task completes.
+/// Returns a `JoinHandle<()>` from [`spawn_send`], a thin wrapper around
+/// `tokio::spawn`, which can be awaited to ensure the task completes. Because
+/// the `Send` bound lives on `spawn_send`, capturing a non-`Send` value such as
+/// an `Rc` in the body is reported against the task body rather than inside Tokio.
 ///
 /// # Macro Expansion Examples
 ///
@@ -183,7 +253,7 @@ pub use a::A;
 /// ```rust,ignore
 /// {
 ///     let data = ::core::clone::Clone::clone(&data);
-///     tokio::spawn({
+///     tspawn::spawn_send({
 ///         let data = data.read();
 ///         async move { println!("Value: {}", *data); }
 ///     })
@@ -200,7 +270,7 @@ pub use a::A;
 ///     let a = ::core::clone::Clone::clone(&a);
 ///     let b = ::core::clone::Clone::clone(&b);
 ///     let c = ::core::clone::Clone::clone(&c);
-///     tokio::spawn({
+///     tspawn::spawn_send({
 ///         let mut b = b.write();
 ///         let c = c.read();
 ///         async move { *b += *c + a.get(); }
@@ -210,11 +280,323 @@ pub use a::A;
 ///
 /// The macro is fully variadic and can handle any number of variables with any
 /// combination of `ref`, `mut`, and bare modifiers.
+///
+/// # Capture Lists
+///
+/// Captures can also be grouped in square brackets, closure-style, which reads
+/// better when there are many of them. The bracketed form expands exactly like
+/// the flat form:
+///
+/// ```rust
+/// use tspawn::{A, tspawn};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = A::new(1);
+/// let counter = A::new(0);
+///
+/// tspawn!([ref data, mut counter] async {
+///     *counter += *data;
+/// }).await?;
+///
+/// tspawn!([mut counter], {
+///     *counter += 1;
+/// }).await?;
+///
+/// assert_eq!(counter.get(), 2);
+/// # Ok(())
+/// # }
+/// ```
 #[macro_export]
 macro_rules! tspawn {
-    // Entry point: parse all variables and body
+    // Entry point: hand everything to the parser with the default spawner
     ($($input:tt)*) => {
-        $crate::tspawn_internal!(@parse [] [] $($input)*)
+        $crate::tspawn_internal!(@start [$crate::spawn_send] $($input)*)
+    };
+}
+
+/// Spawns a tokio task like [`tspawn!`], attaching the given `tracing::Span` to it.
+///
+/// The span is attached to the task's future via [`tracing::Instrument`], so every
+/// event emitted inside the body is recorded under it. This makes it easy to
+/// correlate a task with the request span that created it. Captures follow the
+/// same rules as [`tspawn!`], including the bracketed capture list.
+///
+/// Only available with the `tracing` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{A, tspawn_in_span};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = A::new(0);
+/// let span = tracing::info_span!("request", id = 7);
+///
+/// tspawn_in_span!(span, mut data, {
+///     tracing::info!("updating");
+///     *data += 1;
+/// }).await?;
+///
+/// assert_eq!(data.get(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! tspawn_in_span {
+    ($span:expr, $($input:tt)*) => {{
+        let span: $crate::__private::tracing::Span = $span;
+        $crate::tspawn_internal!(
+            @start
+            [(move |future| $crate::spawn_send($crate::__private::tracing::Instrument::instrument(future, span)))]
+            $($input)*
+        )
+    }};
+}
+
+/// Spawns a task over one element of a slice of handles, like [`tspawn!`].
+///
+/// `tspawn_index!(shards, i, mut, { ... })` clones `shards[i]` and spawns a task
+/// with it captured under the given modifier (`ref`, `mut`, or any other
+/// [`tspawn!`] modifier); omit the modifier to capture the bare handle. Inside the
+/// body, the slice name refers to the captured element.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{A, tspawn_index};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let shards = vec![A::new(0), A::new(0), A::new(0)];
+///
+/// for i in 0..shards.len() {
+///     tspawn_index!(shards, i, mut, {
+///         *shards += i;
+///     }).await?;
+/// }
+///
+/// assert_eq!(shards[2].get(), 2);
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! tspawn_index {
+    ($slice:ident, $index:expr, $modifier:tt, $body:block) => {{
+        let $slice = ::core::clone::Clone::clone(&$slice[$index]);
+        $crate::tspawn!($modifier $slice, $body)
+    }};
+
+    ($slice:ident, $index:expr, $body:block) => {{
+        let $slice = ::core::clone::Clone::clone(&$slice[$index]);
+        $crate::tspawn!($slice, $body)
+    }};
+}
+
+/// Spawns a task that calls an async function with the captured handles.
+///
+/// `tspawn_call!(func, a, mut b)` captures `a` and `b` exactly as
+/// `tspawn!(a, mut b, { ... })` would, then runs `func(a, b).await` as the task
+/// body. Captures are passed to the function in the order they are declared, as
+/// the bare handle or as the guard produced by their modifier.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{A, WriteGuard, tspawn_call};
+///
+/// async fn record(source: A<String>, mut log: WriteGuard<Vec<String>>) {
+///     log.push(source.get());
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let source = A::new(String::from("started"));
+/// let log = A::new(Vec::new());
+///
+/// tspawn_call!(record, source, mut log).await?;
+///
+/// assert_eq!(log.get(), vec!["started"]);
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! tspawn_call {
+    ($func:path $(, $($captures:tt)*)?) => {
+        $crate::tspawn_internal!(@call [$func] [] [$($($captures)*)?] $($($captures)*)?)
+    };
+}
+
+/// Spawns a task like [`tspawn!`], with a `tokio::task_local!` set for its duration.
+///
+/// `tspawn_with_local!(LOCAL = value, ref data, { ... })` evaluates `value`,
+/// applies the usual capture rules to the remaining arguments, and runs the task
+/// body inside `LOCAL.scope(value, ...)`, so any code called from the task can
+/// read the value without it being passed around.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{A, tspawn_with_local};
+///
+/// tokio::task_local! {
+///     static REQUEST_ID: u64;
+/// }
+///
+/// fn log_line(message: &str) -> String {
+///     format!("[{}] {message}", REQUEST_ID.get())
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let log = A::new(Vec::new());
+///
+/// tspawn_with_local!(REQUEST_ID = 42, mut log, {
+///     log.push(log_line("handled"));
+/// }).await?;
+///
+/// assert_eq!(log.get(), vec!["[42] handled"]);
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! tspawn_with_local {
+    ($local:path = $value:expr, $($input:tt)*) => {{
+        let value = $value;
+        $crate::tspawn_internal!(
+            @start
+            [(move |future| $crate::spawn_send($local.scope(value, future)))]
+            $($input)*
+        )
+    }};
+}
+
+/// Races several `tspawn!`-style task bodies and returns the first to finish.
+///
+/// Each branch is a bracketed capture list followed by `=>` and a block; the
+/// captures follow the same rules as [`tspawn!`]. Every branch is spawned as its
+/// own task when the returned future is first polled, and `tokio::select!`
+/// resolves it with the winning body's value (as a `Result`, like a
+/// `JoinHandle`). The remaining tasks are wrapped in [`AbortOnDrop`] and aborted
+/// as soon as the race is decided, so their futures are dropped and any guards
+/// they held are released.
+///
+/// Locks are acquired when the branches are spawned, so branches should not
+/// write-lock the same handle.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use tspawn::{A, tselect};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let cache = A::new(Some(7));
+/// let database = A::new(7);
+///
+/// let value = tselect! {
+///     [ref cache] => { cache.unwrap_or_default() },
+///     [database] => {
+///         tokio::time::sleep(Duration::from_secs(1)).await;
+///         database.get()
+///     },
+/// }
+/// .await?;
+///
+/// assert_eq!(value, 7);
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! tselect {
+    ($([$($captures:tt)*] => $body:block),+ $(,)?) => {
+        async {
+            $crate::__private::tokio::select! {
+                $(result = $crate::AbortOnDrop::new($crate::tspawn!([$($captures)*], $body)) => result,)+
+            }
+        }
+    };
+}
+
+/// Read-locks several handles at once and evaluates an expression over all values.
+///
+/// The locks are acquired in order of the shared lock's address, as with
+/// [`A::acquire_two`], and held together while the expression runs, so the
+/// values form a consistent snapshot: no writer that also locks the handles
+/// in address order can be observed half-way through. With the `lock_order`
+/// feature, lower levels are locked first and the address breaks ties. The
+/// handles may wrap different types, and the same handle may appear more than
+/// once.
+///
+/// The part after `=>` names one binding per handle, in the same order; each
+/// binding is a shared reference to that handle's value.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{read_all, A};
+///
+/// let price = A::new(12);
+/// let quantity = A::new(3);
+/// let currency = A::new("EUR");
+///
+/// let total = read_all!(price, quantity, currency => |p, q, c| format!("{} {c}", p * q));
+/// assert_eq!(total, "36 EUR");
+/// ```
+#[macro_export]
+macro_rules! read_all {
+    ($($handle:expr),+ $(,)? => |$($value:ident),+ $(,)?| $body:expr) => {{
+        // Bind all handles at once so a binding cannot shadow a later handle
+        let ($(mut $value,)+) = ($((&$handle, None),)+);
+        let mut keys = [$($crate::__private::lock_key($value.0)),+];
+        keys.sort_unstable();
+        for key in keys {
+            $(
+                if $value.1.is_none() && $crate::__private::lock_key($value.0) == key {
+                    // A handle listed twice shares the lock it already holds
+                    $value.1 = Some($crate::__private::read_recursive($value.0));
+                }
+            )+
+        }
+        $(let $value = $value.1.expect("every handle is locked");)+
+        $(let $value = &*$value;)+
+        $body
+    }};
+}
+
+/// Clones the values of several handles as one consistent snapshot.
+///
+/// This is [`read_all!`] with every value cloned out: all read locks are
+/// taken in address order, the values are cloned while the locks are held
+/// together, and the locks are released before the tuple is returned. The
+/// handles may wrap different types, each of which must implement `Clone`.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{snapshot_all, A};
+///
+/// let name = A::new(String::from("widget"));
+/// let stock = A::new(4);
+/// let tags = A::new(vec!["new"]);
+///
+/// let (name, stock, tags) = snapshot_all!(name, stock, tags);
+/// assert_eq!((name.as_str(), stock, tags), ("widget", 4, vec!["new"]));
+/// ```
+#[macro_export]
+macro_rules! snapshot_all {
+    // Each step introduces its own `value` binding; hygiene keeps them distinct
+    (@bind [$(($handle:expr, $value:ident))*]) => {
+        $crate::read_all!($($handle),* => |$($value),*| ($(::std::clone::Clone::clone($value),)*))
+    };
+    (@bind [$($bound:tt)*] $head:expr $(, $rest:expr)*) => {
+        $crate::snapshot_all!(@bind [$($bound)* ($head, value)] $($rest),*)
+    };
+    ($($handle:expr),+ $(,)?) => {
+        $crate::snapshot_all!(@bind [] $($handle),+)
     };
 }
 
@@ -222,76 +604,268 @@ macro_rules! tspawn {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! tspawn_internal {
+    // Call form: all captures collected, spawn a task calling the function with them
+    (@call [$func:path] [$($arg:ident)*] [$($captures:tt)*]) => {{
+        // Write guards are moved into the call, so their `let mut` bindings go unused
+        #[allow(unused_mut)]
+        let handle = $crate::tspawn_internal!(
+            @start [$crate::spawn_send] $($captures)* { $func($($arg),*).await }
+        );
+        handle
+    }};
+
+    // Call form: collect the name of a capture with a modifier
+    (@call [$func:path] [$($arg:ident)*] [$($captures:tt)*] $modifier:tt $var:ident $(, $($rest:tt)*)?) => {
+        $crate::tspawn_internal!(@call [$func] [$($arg)* $var] [$($captures)*] $($($rest)*)?)
+    };
+
+    // Call form: collect the name of a bare capture
+    (@call [$func:path] [$($arg:ident)*] [$($captures:tt)*] $var:ident $(, $($rest:tt)*)?) => {
+        $crate::tspawn_internal!(@call [$func] [$($arg)* $var] [$($captures)*] $($($rest)*)?)
+    };
+
+    // Entry point: bracketed capture list followed by an async block
+    (@start [$($spawn:tt)*] [$($captures:tt)*] async $body:block) => {
+        $crate::tspawn_internal!(@parse [$($spawn)*] [] [] [] $($captures)* $body)
+    };
+
+    // Entry point: bracketed capture list followed by a block
+    (@start [$($spawn:tt)*] [$($captures:tt)*], $body:block) => {
+        $crate::tspawn_internal!(@parse [$($spawn)*] [] [] [] $($captures)* $body)
+    };
+
+    // Entry point: flat list of variables followed by a block
+    (@start [$($spawn:tt)*] $($input:tt)*) => {
+        $crate::tspawn_internal!(@parse [$($spawn)*] [] [] [] $($input)*)
+    };
+
     // Base case: no more input, spawn the task
-    (@parse [$($clone:tt)*] [$($lock:tt)*] $body:block) => {{
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [] $body:block) => {{
+        $($clone)*
+        $($spawn)*({
+            $crate::__tspawn_begin!();
+            $($lock)*
+            $crate::__tspawn_task!($crate::__tspawn_async!($body))
+        })
+    }};
+
+    // Base case with `try_*` captures: skip the body if any lock was contended
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)+] $body:block) => {{
         $($clone)*
-        tokio::spawn({
+        $($spawn)*({
+            $crate::__tspawn_begin!();
             $($lock)*
-            async move $body
+            $crate::__tspawn_task!($crate::__tspawn_async!({
+                $($check)*
+                $body
+            }))
         })
     }};
 
+    // Parse: read var (alias of ref)
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] read $var:ident $($rest:tt)*) => {
+        $crate::tspawn_internal!(
+            @parse [$($spawn)*] [$($clone)*] [$($lock)*] [$($check)*] ref $var $($rest)*
+        )
+    };
+
+    // Parse: write var (alias of mut)
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] write $var:ident $($rest:tt)*) => {
+        $crate::tspawn_internal!(
+            @parse [$($spawn)*] [$($clone)*] [$($lock)*] [$($check)*] mut $var $($rest)*
+        )
+    };
+
+    // Parse: try_read var
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] try_read $var:ident, $($rest:tt)*) => {
+        $crate::tspawn_internal!(
+            @parse
+            [$($spawn)*]
+            [$($clone)* let $var = ::core::clone::Clone::clone(&$var);]
+            [$($lock)* let $var = $crate::__tspawn_guard!($var, $var.try_read_arc());]
+            [$($check)* let ::core::option::Option::Some($var) = $var else { return; };]
+            $($rest)*
+        )
+    };
+
+    // Parse: try_write var
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] try_write $var:ident, $($rest:tt)*) => {
+        $crate::tspawn_internal!(
+            @parse
+            [$($spawn)*]
+            [$($clone)* let $var = ::core::clone::Clone::clone(&$var);]
+            [$($lock)* let $var = $crate::__tspawn_guard!($var, $var.try_write_arc());]
+            [$($check)* let ::core::option::Option::Some(mut $var) = $var else { return; };]
+            $($rest)*
+        )
+    };
+
     // Parse: ref var
-    (@parse [$($clone:tt)*] [$($lock:tt)*] ref $var:ident, $($rest:tt)*) => {
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] ref $var:ident, $($rest:tt)*) => {
         $crate::tspawn_internal!(
             @parse
+            [$($spawn)*]
             [$($clone)* let $var = ::core::clone::Clone::clone(&$var);]
-            [$($lock)* let $var = $var.read();]
+            [$($lock)* let $var = $crate::__tspawn_guard!($var, $var.read());]
+            [$($check)*]
             $($rest)*
         )
     };
 
     // Parse: mut var
-    (@parse [$($clone:tt)*] [$($lock:tt)*] mut $var:ident, $($rest:tt)*) => {
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] mut $var:ident, $($rest:tt)*) => {
         $crate::tspawn_internal!(
             @parse
+            [$($spawn)*]
             [$($clone)* let $var = ::core::clone::Clone::clone(&$var);]
-            [$($lock)* let mut $var = $var.write();]
+            [$($lock)* let mut $var = $crate::__tspawn_guard!($var, $var.write());]
+            [$($check)*]
             $($rest)*
         )
     };
 
     // Parse: bare var
-    (@parse [$($clone:tt)*] [$($lock:tt)*] $var:ident, $($rest:tt)*) => {
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] $var:ident, $($rest:tt)*) => {
         $crate::tspawn_internal!(
             @parse
+            [$($spawn)*]
             [$($clone)* let $var = ::core::clone::Clone::clone(&$var);]
             [$($lock)*]
+            [$($check)*]
             $($rest)*
         )
     };
 
+    // Parse: try_read var (last variable, no comma)
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] try_read $var:ident $body:block) => {
+        $crate::tspawn_internal!(
+            @parse [$($spawn)*] [$($clone)*] [$($lock)*] [$($check)*] try_read $var, $body
+        )
+    };
+
+    // Parse: try_write var (last variable, no comma)
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] try_write $var:ident $body:block) => {
+        $crate::tspawn_internal!(
+            @parse [$($spawn)*] [$($clone)*] [$($lock)*] [$($check)*] try_write $var, $body
+        )
+    };
+
     // Parse: ref var (last variable, no comma)
-    (@parse [$($clone:tt)*] [$($lock:tt)*] ref $var:ident $body:block) => {
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] ref $var:ident $body:block) => {
         $crate::tspawn_internal!(
             @parse
+            [$($spawn)*]
             [$($clone)* let $var = ::core::clone::Clone::clone(&$var);]
-            [$($lock)* let $var = $var.read();]
+            [$($lock)* let $var = $crate::__tspawn_guard!($var, $var.read());]
+            [$($check)*]
             $body
         )
     };
 
     // Parse: mut var (last variable, no comma)
-    (@parse [$($clone:tt)*] [$($lock:tt)*] mut $var:ident $body:block) => {
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] mut $var:ident $body:block) => {
         $crate::tspawn_internal!(
             @parse
+            [$($spawn)*]
             [$($clone)* let $var = ::core::clone::Clone::clone(&$var);]
-            [$($lock)* let mut $var = $var.write();]
+            [$($lock)* let mut $var = $crate::__tspawn_guard!($var, $var.write());]
+            [$($check)*]
             $body
         )
     };
 
     // Parse: bare var (last variable, no comma)
-    (@parse [$($clone:tt)*] [$($lock:tt)*] $var:ident $body:block) => {
+    (@parse [$($spawn:tt)*] [$($clone:tt)*] [$($lock:tt)*] [$($check:tt)*] $var:ident $body:block) => {
         $crate::tspawn_internal!(
             @parse
+            [$($spawn)*]
             [$($clone)* let $var = ::core::clone::Clone::clone(&$var);]
             [$($lock)*]
+            [$($check)*]
             $body
         )
     };
 }
 
+// Forgets captures registered by an earlier spawn that panicked while locking
+#[cfg(any(feature = "await_check", feature = "lock_order"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tspawn_begin {
+    () => {
+        $crate::__private::held::begin()
+    };
+}
+
+#[cfg(not(any(feature = "await_check", feature = "lock_order")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tspawn_begin {
+    () => {};
+}
+
+// Registers a capture's guard with the task about to be spawned
+#[cfg(any(feature = "await_check", feature = "lock_order"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tspawn_guard {
+    ($var:ident, $guard:expr) => {
+        $crate::__private::held::register(stringify!($var), $guard)
+    };
+}
+
+#[cfg(not(any(feature = "await_check", feature = "lock_order")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tspawn_guard {
+    ($var:ident, $guard:expr) => {
+        $guard
+    };
+}
+
+// Wraps a task's future so it can track the guards it captured
+#[cfg(any(feature = "await_check", feature = "lock_order"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tspawn_task {
+    ($future:expr) => {
+        $crate::__private::held::Tracked::new($future)
+    };
+}
+
+#[cfg(not(any(feature = "await_check", feature = "lock_order")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tspawn_task {
+    ($future:expr) => {
+        $future
+    };
+}
+
+// Re-exports used by the macros; not part of the public API
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(any(feature = "await_check", feature = "lock_order"))]
+    pub mod held {
+        pub use crate::held::{begin, register, Guard, Held, Tracked};
+    }
+    pub use tokio;
+    #[cfg(feature = "tracing")]
+    pub use tracing;
+
+    use crate::A;
+
+    pub fn lock_key<T>(handle: &A<T>) -> (u32, usize) {
+        handle.lock_key()
+    }
+
+    pub fn read_recursive<T>(
+        handle: &A<T>,
+    ) -> crate::guard::Locked<parking_lot::RwLockReadGuard<'_, T>> {
+        handle.lock_read_recursive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -350,6 +924,40 @@ mod tests {
         assert_eq!(data.get(), "Hello, World!");
     }
 
+    #[test]
+    fn test_reset_keeps_capacity() {
+        let data = A::new(Vec::with_capacity(64));
+        data.update(|v| v.extend(0..32));
+        let capacity = data.read().capacity();
+
+        data.reset();
+        assert_eq!(data.read().len(), 0);
+        assert_eq!(data.read().capacity(), capacity);
+
+        let text = A::new(String::from("Hello"));
+        text.reset();
+        assert_eq!(text.get(), "");
+
+        let value = A::new(7);
+        assert_eq!(value.replace_default(), 7);
+        assert_eq!(value.get(), 0);
+    }
+
+    #[test]
+    fn test_result_accessors() {
+        let conn: A<Result<u32, String>> = A::new(Ok(1));
+        assert!(conn.is_ok());
+        assert_eq!(conn.ok_cloned(), Some(1));
+        assert_eq!(conn.replace_ok(2), Ok(1));
+        assert_eq!(conn.ok_cloned(), Some(2));
+
+        let failed: A<Result<u32, String>> = A::new(Err("refused".to_string()));
+        assert!(!failed.is_ok());
+        assert_eq!(failed.ok_cloned(), None);
+        assert_eq!(failed.replace_ok(3), Err("refused".to_string()));
+        assert!(failed.is_ok());
+    }
+
     #[test]
     fn test_from_and_into_inner() {
         use parking_lot::RwLock;
@@ -506,16 +1114,50 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_tspawn_many_variables() {
-        // Test with 8 variables to exceed the old hardcoded limit
-        let v0 = A::new(0);
-        let v1 = A::new(1);
-        let v2 = A::new(2);
-        let v3 = A::new(3);
-        let v4 = A::new(4);
-        let v5 = A::new(5);
-        let v6 = A::new(6);
-        let v7 = A::new(7);
+    async fn test_tspawn_capture_list() {
+        let flat_a = A::new(1);
+        let flat_b = A::new(10);
+        tspawn!(ref flat_a, mut flat_b, {
+            *flat_b += *flat_a;
+        })
+        .await
+        .unwrap();
+
+        let a = A::new(1);
+        let b = A::new(10);
+        tspawn!([ref a, mut b], {
+            *b += *a;
+        })
+        .await
+        .unwrap();
+        assert_eq!(b.get(), flat_b.get());
+
+        tspawn!([ref a, mut b] async {
+            *b += *a;
+        })
+        .await
+        .unwrap();
+        assert_eq!(b.get(), 12);
+
+        tspawn!([b], {
+            b.update(|x| *x += 1);
+        })
+        .await
+        .unwrap();
+        assert_eq!(b.get(), 13);
+    }
+
+    #[tokio::test]
+    async fn test_tspawn_many_variables() {
+        // Test with 8 variables to exceed the old hardcoded limit
+        let v0 = A::new(0);
+        let v1 = A::new(1);
+        let v2 = A::new(2);
+        let v3 = A::new(3);
+        let v4 = A::new(4);
+        let v5 = A::new(5);
+        let v6 = A::new(6);
+        let v7 = A::new(7);
 
         tspawn!(v0, mut v1, ref v2, v3, ref v4, mut v5, v6, ref v7, {
             *v1 += *v2 + *v4 + *v7; // 1 + 2 + 4 + 7 = 14
@@ -527,4 +1169,2663 @@ mod tests {
         assert_eq!(v1.get(), 14);
         assert_eq!(v5.get(), 14);
     }
+
+    #[cfg(feature = "instrumented")]
+    #[test]
+    fn test_write_contention_counter() {
+        let data = A::new(0);
+        assert_eq!(data.write_contention(), 0);
+
+        let guard = data.write();
+        let writer = {
+            let data = data.clone();
+            std::thread::spawn(move || data.set(1))
+        };
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        drop(guard);
+        writer.join().unwrap();
+
+        assert!(data.write_contention() > 0);
+        assert_eq!(data.get(), 1);
+    }
+
+    #[test]
+    fn test_load_store_aliases() {
+        let data = A::new(1);
+        assert_eq!(data.load(), data.get());
+
+        data.store(2);
+        assert_eq!(data.get(), 2);
+
+        data.set(3);
+        assert_eq!(data.load(), 3);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn test_tspawn_in_span() {
+        use std::sync::{Arc, Mutex};
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+        use tracing_subscriber::registry::LookupSpan;
+
+        struct RecordSpans(Arc<Mutex<Vec<String>>>);
+
+        impl<S> tracing_subscriber::Layer<S> for RecordSpans
+        where
+            S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+        {
+            fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+                if let Some(span) = ctx.event_span(event) {
+                    self.0.lock().unwrap().push(span.name().to_string());
+                }
+            }
+        }
+
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(RecordSpans(recorded.clone()));
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        let data = A::new(0);
+        let span = tracing::info_span!("request");
+        tspawn_in_span!(span, mut data, {
+            tracing::info!("inside task");
+            *data += 1;
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(data.get(), 1);
+        assert_eq!(*recorded.lock().unwrap(), vec!["request".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_try_arc_guards_move_into_task() {
+        let data = A::new(1);
+
+        let reader = data.read();
+        assert!(data.try_write_arc().is_none());
+        assert!(data.try_read_arc().is_some());
+        drop(reader);
+
+        if let Some(mut guard) = data.try_write_arc() {
+            tokio::spawn(async move {
+                *guard += 1;
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(data.get(), 2);
+
+        let writer = data.write();
+        assert!(data.try_read_arc().is_none());
+        drop(writer);
+    }
+
+    #[test]
+    fn test_entry_update() {
+        use std::collections::HashMap;
+
+        let scores = A::new(HashMap::new());
+        scores.entry_update(1u32, || 100, |score| *score += 5);
+        assert_eq!(scores.read().get(&1), Some(&105));
+
+        scores.entry_update(1u32, || 0, |score| *score -= 10);
+        assert_eq!(scores.read().get(&1), Some(&95));
+        assert_eq!(scores.read().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_handle_unique_after_task() {
+        let data = A::new(0);
+        let guard = data.leak_guard();
+
+        let handle = tspawn!(data, {
+            data.update(|x| *x += 1);
+        });
+        handle.await.unwrap();
+
+        data.assert_unique();
+        drop(guard);
+        assert_eq!(data.get(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "LeakGuard")]
+    fn test_leak_guard_detects_leak() {
+        let data = A::new(0);
+        let guard = data.leak_guard();
+        let leaked = data.clone();
+        drop(guard);
+        drop(leaked);
+    }
+
+    #[tokio::test]
+    async fn test_tspawn_explicit_lock_kinds() {
+        let cache = A::new(0);
+        let source = A::new(5);
+
+        tspawn!(write cache, read source, {
+            *cache += *source;
+        })
+        .await
+        .unwrap();
+        assert_eq!(cache.get(), 5);
+
+        tspawn!(try_write cache, try_read source, {
+            *cache += *source;
+        })
+        .await
+        .unwrap();
+        assert_eq!(cache.get(), 10);
+
+        let held = cache.read();
+        tspawn!(try_write cache, {
+            *cache = 0;
+        })
+        .await
+        .unwrap();
+        drop(held);
+        assert_eq!(cache.get(), 10);
+    }
+
+    #[test]
+    #[allow(clippy::mutable_key_type)]
+    fn test_by_address_keys() {
+        use std::collections::HashMap;
+
+        let first = A::new(1);
+        let second = A::new(1);
+
+        let mut labels = HashMap::new();
+        labels.insert(ByAddress(first.clone()), "first");
+        labels.insert(ByAddress(first.clone()), "first again");
+        labels.insert(ByAddress(second.clone()), "second");
+
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[&ByAddress(first.clone())], "first again");
+        assert_eq!(labels[&ByAddress(second)], "second");
+        assert_eq!(first.as_ptr(), first.clone().as_ptr());
+    }
+
+    #[test]
+    fn test_try_update_for_timeout() {
+        use std::time::Duration;
+
+        let data = A::new(0);
+        assert!(data.try_update_for(Duration::from_millis(10), |x| *x += 1));
+
+        let guard = data.write();
+        let updated = {
+            let data = data.clone();
+            std::thread::spawn(move || data.try_update_for(Duration::from_millis(20), |x| *x = 100))
+                .join()
+                .unwrap()
+        };
+        drop(guard);
+
+        assert!(!updated);
+        assert_eq!(data.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tselect_first_wins() {
+        use std::time::Duration;
+
+        let fast = A::new(1);
+        let slow = A::new(2);
+        let finished = A::new(false);
+
+        let winner = tselect! {
+            [ref fast] => { *fast },
+            [ref slow, finished] => {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                finished.set(true);
+                *slow
+            },
+        }
+        .await
+        .unwrap();
+        assert_eq!(winner, 1);
+
+        // The losing task is aborted, which releases its read guard.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(slow.try_write_arc().is_some());
+        assert!(!finished.get());
+    }
+
+    #[test]
+    fn test_read_and_then() {
+        let balance = A::new(50);
+        let withdraw = |amount: i32| {
+            balance.read_and_then(|b| {
+                if *b >= amount {
+                    Ok(*b - amount)
+                } else {
+                    Err(format!("insufficient funds: {}", b))
+                }
+            })
+        };
+
+        assert_eq!(withdraw(20), Ok(30));
+        assert_eq!(withdraw(80), Err("insufficient funds: 50".to_string()));
+    }
+
+    #[test]
+    fn test_with_mut_sole() {
+        let mut data = A::new(0);
+        assert_eq!(
+            data.with_mut_sole(|x| {
+                *x = 10;
+                *x
+            }),
+            Some(10)
+        );
+
+        let cloned = data.clone();
+        assert_eq!(data.with_mut_sole(|x| *x = 20), None);
+        assert_eq!(cloned.get(), 10);
+
+        drop(cloned);
+        assert_eq!(data.with_mut_sole(|x| *x += 1), Some(()));
+        assert_eq!(data.get(), 11);
+    }
+
+    #[test]
+    fn test_with_both_mut_transfers() {
+        let left = A::new(1_000);
+        let right = A::new(1_000);
+
+        let forward = {
+            let (left, right) = (left.clone(), right.clone());
+            std::thread::spawn(move || {
+                for _ in 0..1_000 {
+                    A::with_both_mut(&left, &right, |from, to| {
+                        *from -= 1;
+                        *to += 1;
+                    });
+                }
+            })
+        };
+        let backward = {
+            let (left, right) = (left.clone(), right.clone());
+            std::thread::spawn(move || {
+                for _ in 0..500 {
+                    A::with_both_mut(&right, &left, |from, to| {
+                        *from -= 1;
+                        *to += 1;
+                    });
+                }
+            })
+        };
+        forward.join().unwrap();
+        backward.join().unwrap();
+
+        assert_eq!(left.get(), 500);
+        assert_eq!(right.get(), 1_500);
+        assert_eq!(left.get() + right.get(), 2_000);
+    }
+
+    #[tokio::test]
+    async fn test_read_clone_arc_shared_by_consumers() {
+        use std::sync::Arc;
+
+        let config = A::new(vec![1, 2, 3]);
+        let snapshot = config.read_clone_arc();
+
+        let mut consumers = Vec::new();
+        for _ in 0..8 {
+            let snapshot = Arc::clone(&snapshot);
+            consumers.push(tokio::spawn(async move {
+                (
+                    Arc::as_ptr(&snapshot) as usize,
+                    snapshot.iter().sum::<i32>(),
+                )
+            }));
+        }
+        config.update(|v| v.push(4));
+
+        for consumer in consumers {
+            let (ptr, sum) = consumer.await.unwrap();
+            assert_eq!(ptr, Arc::as_ptr(&snapshot) as usize);
+            assert_eq!(sum, 6);
+        }
+        assert_eq!(Arc::strong_count(&snapshot), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ordered_tasks_results_in_index_order() {
+        use std::time::Duration;
+
+        let shards = [A::new(1), A::new(2), A::new(3)];
+        let mut tasks = OrderedTasks::new();
+
+        // Later indices finish first.
+        for (index, shard) in shards.iter().cloned().enumerate().rev() {
+            let delay = Duration::from_millis(10 * (3 - index as u64));
+            tasks.spawn(
+                index,
+                tspawn!(shard, {
+                    tokio::time::sleep(delay).await;
+                    shard.get() * 10
+                }),
+            );
+        }
+
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks.join_ordered().await.unwrap(), vec![10, 20, 30]);
+    }
+
+    #[cfg(feature = "lock_owner_tracking")]
+    #[test]
+    fn test_current_writer_reported() {
+        let data = A::new(0);
+        assert_eq!(data.current_writer(), None);
+
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let holder = {
+            let data = data.clone();
+            std::thread::spawn(move || {
+                let _guard = data.write();
+                locked_tx.send(std::thread::current().id()).unwrap();
+                release_rx.recv().unwrap();
+            })
+        };
+
+        let holder_id = locked_rx.recv().unwrap();
+        assert_eq!(data.current_writer(), Some(holder_id));
+        let (_, location) = data.current_writer_location().unwrap();
+        assert_eq!(location.file(), file!());
+
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+        assert_eq!(data.current_writer(), None);
+    }
+
+    #[test]
+    fn test_any_registry_downcast() {
+        use std::collections::HashMap;
+
+        let counter = A::new(1);
+        let name = A::new(String::from("tspawn"));
+
+        let mut registry: HashMap<&str, AnyA> = HashMap::new();
+        registry.insert("counter", counter.clone().into_any());
+        registry.insert("name", name.clone().into_any());
+
+        let erased = registry.remove("counter").unwrap();
+        assert!(erased.is::<i32>());
+        let erased = erased.downcast::<String>().err().unwrap();
+        let restored = erased.downcast::<i32>().ok().unwrap();
+        restored.set(2);
+        assert_eq!(counter.get(), 2);
+
+        let name_handle = registry["name"].downcast_ref::<String>().unwrap();
+        name_handle.update(|s| s.push('!'));
+        assert_eq!(name.get(), "tspawn!");
+    }
+
+    #[test]
+    fn test_update_retry_under_contention() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // A write sneaking in during the first computation forces a retry.
+        let data = A::new(0);
+        let calls = AtomicUsize::new(0);
+        let result = data.update_retry(|current| {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                data.set(10);
+            }
+            (current + 1, *current)
+        });
+        assert_eq!(result, 10);
+        assert_eq!(data.get(), 11);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Competing updaters never lose an increment.
+        let counter = A::new(0);
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let counter = counter.clone();
+                let attempts = attempts.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        counter.update_retry(|current| {
+                            attempts.fetch_add(1, Ordering::SeqCst);
+                            (current + 1, ())
+                        });
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(counter.get(), 400);
+        assert!(attempts.load(Ordering::SeqCst) >= 400);
+    }
+
+    #[tokio::test]
+    async fn test_read_guard_in_hand_written_task() {
+        let data = A::new(String::from("shared"));
+
+        let guard = data.read_for_task();
+        let task = tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            guard.len()
+        });
+        assert_eq!(task.await.unwrap(), 6);
+
+        let guard = data.read();
+        let task = tokio::spawn(async move { guard.to_uppercase() });
+        assert_eq!(task.await.unwrap(), "SHARED");
+
+        // Both guards were dropped with their tasks.
+        assert!(data.try_write_arc().is_some());
+    }
+
+    #[test]
+    fn test_fold_handles() {
+        let counters: Vec<_> = (1..=10).map(A::new).collect();
+        assert_eq!(A::fold(&counters, 0, |acc, x| acc + x), 55);
+
+        counters[0].set(100);
+        assert_eq!(A::fold(&counters, 0, |acc, x| acc + x), 154);
+        assert_eq!(A::fold(&counters[..0], 7, |acc, x| acc + x), 7);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_changes_stream() {
+        use tokio_stream::StreamExt;
+
+        let data = A::new(0);
+        let mut changes = data.changes();
+
+        data.set(1);
+        data.clone().update(|x| *x += 10);
+
+        assert_eq!(changes.next().await, Some(1));
+        assert_eq!(changes.next().await, Some(11));
+    }
+
+    #[test]
+    fn test_map_into() {
+        let small = A::new(200u8);
+        let wide: A<u32> = small.map_into();
+        assert_eq!(wide.get(), 200);
+
+        wide.update(|x| *x += 100);
+        assert_eq!(wide.get(), 300);
+        assert_eq!(small.get(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_tspawn_index_over_shards() {
+        let shards = [A::new(vec![]), A::new(vec![]), A::new(vec![])];
+
+        let mut handles = Vec::new();
+        for i in 0..shards.len() {
+            handles.push(tspawn_index!(shards, i, mut, {
+                shards.push(i * 10);
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(shards[0].get(), vec![0]);
+        assert_eq!(shards[1].get(), vec![10]);
+        assert_eq!(shards[2].get(), vec![20]);
+
+        let len = tspawn_index!(shards, 1, ref, { shards.len() })
+            .await
+            .unwrap();
+        assert_eq!(len, 1);
+
+        tspawn_index!(shards, 2, {
+            shards.update(|v| v.push(21));
+        })
+        .await
+        .unwrap();
+        assert_eq!(shards[2].get(), vec![20, 21]);
+    }
+
+    #[test]
+    fn test_try_clone_value() {
+        let data = A::new(vec![1, 2]);
+        assert_eq!(data.try_clone_value(), Some(vec![1, 2]));
+
+        let writer = data.write();
+        assert_eq!(data.try_clone_value(), None);
+        drop(writer);
+
+        let reader = data.read();
+        assert_eq!(data.try_clone_value(), Some(vec![1, 2]));
+        drop(reader);
+    }
+
+    #[test]
+    fn test_with_write_or_branches() {
+        use std::time::Duration;
+
+        let data = A::new(0);
+        let acquired = data.with_write_or(
+            Duration::from_millis(10),
+            |x| {
+                *x += 1;
+                true
+            },
+            || false,
+        );
+        assert!(acquired);
+
+        let reader = data.read();
+        let acquired = data.with_write_or(
+            Duration::from_millis(10),
+            |x| {
+                *x += 1;
+                true
+            },
+            || false,
+        );
+        drop(reader);
+        assert!(!acquired);
+        assert_eq!(data.get(), 1);
+    }
+
+    #[test]
+    fn test_weak_registry_prunes_dropped_subscribers() {
+        let registry = A::new(Vec::new());
+        let first = A::new(0);
+        let second = A::new(0);
+        first.subscribe_weak(&registry);
+        second.subscribe_weak(&registry);
+
+        assert_eq!(registry.broadcast(|x| *x += 1), 2);
+        assert_eq!(first.get(), 1);
+        assert_eq!(second.get(), 1);
+
+        drop(second);
+        assert_eq!(registry.broadcast(|x| *x += 1), 1);
+        assert_eq!(registry.read().len(), 1);
+        assert_eq!(first.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_read_then_spawn_snapshot() {
+        let data = A::new(String::from("snapshot"));
+
+        let handle = data.read_then_spawn(|value| async move {
+            tokio::task::yield_now().await;
+            value.len()
+        });
+        data.set(String::from("changed afterwards"));
+
+        assert_eq!(handle.await.unwrap(), 8);
+    }
+
+    #[test]
+    fn test_update_when_status_gate() {
+        #[derive(Debug, Clone, PartialEq)]
+        enum Status {
+            Pending,
+            Running,
+        }
+
+        #[derive(Clone)]
+        struct Order {
+            status: Status,
+            worker: Option<u32>,
+        }
+
+        let order = A::new(Order {
+            status: Status::Pending,
+            worker: None,
+        });
+
+        let claim = |worker: u32| {
+            order.update_when(
+                |o| &o.status,
+                &Status::Pending,
+                |o| {
+                    o.status = Status::Running;
+                    o.worker = Some(worker);
+                },
+            )
+        };
+
+        assert!(claim(1));
+        assert!(!claim(2));
+        assert_eq!(order.read().worker, Some(1));
+        assert_eq!(order.read().status, Status::Running);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_unique_then_unwrap() {
+        use std::time::Duration;
+
+        let data = A::new(vec![1]);
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let holder = tspawn!(data, {
+            release_rx.await.unwrap();
+            data.update(|v| v.push(2));
+        });
+        assert_eq!(data.handle_count(), 2);
+
+        release_tx.send(()).unwrap();
+        data.wait_until_unique_async().await;
+        holder.await.unwrap();
+        assert_eq!(data.clone().try_unwrap().ok(), None);
+        assert_eq!(data.try_unwrap().ok(), Some(vec![1, 2]));
+
+        let blocking = A::new(0);
+        let worker = {
+            let blocking = blocking.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                blocking.set(5);
+            })
+        };
+        blocking.wait_until_unique(Duration::from_millis(1));
+        worker.join().unwrap();
+        assert_eq!(blocking.try_unwrap().ok(), Some(5));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_shared_accessors() {
+        #[derive(Shared)]
+        struct Service {
+            requests: u64,
+            peers: Vec<String>,
+        }
+
+        let shared = SharedService::new(0, vec![]);
+        shared.requests().update(|n| *n += 1);
+        shared.peers().update(|p| p.push("alpha".to_string()));
+
+        // The fields are locked independently.
+        let _peers = shared.peers().write();
+        assert_eq!(shared.requests().get(), 1);
+        drop(_peers);
+
+        let converted = Service {
+            requests: 5,
+            peers: vec!["beta".to_string()],
+        }
+        .into_shared();
+        let cloned = converted.clone();
+        cloned.requests().set(6);
+        assert_eq!(converted.requests().get(), 6);
+        assert_eq!(converted.peers().get(), vec!["beta".to_string()]);
+    }
+
+    #[test]
+    fn test_write_variant_matches_only_variant() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Connection {
+            Connected { retries: u32 },
+            Disconnected,
+        }
+
+        fn connected(c: &mut Connection) -> Option<&mut u32> {
+            match c {
+                Connection::Connected { retries } => Some(retries),
+                Connection::Disconnected => None,
+            }
+        }
+
+        let conn = A::new(Connection::Connected { retries: 0 });
+        {
+            let mut retries = conn.write_variant(connected).unwrap();
+            *retries += 3;
+        }
+        assert_eq!(conn.get(), Connection::Connected { retries: 3 });
+
+        conn.set(Connection::Disconnected);
+        assert!(conn.write_variant(connected).is_none());
+        // The lock was released when the variant did not match.
+        assert!(conn.try_write_arc().is_some());
+    }
+
+    #[test]
+    fn test_builder_labeled_fair() {
+        let counter = A::builder(0).fair(true).label("counter").build();
+        let handle = counter.clone();
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let handle = handle.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        handle.update(|x| *x += 1);
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        counter.set(counter.get() + 1);
+        assert_eq!(counter.get(), 401);
+        #[cfg(feature = "labeled")]
+        assert_eq!(handle.label(), Some("counter"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_spawn_periodic_ticks_until_aborted() {
+        use std::time::Duration;
+
+        let ticks = A::new(0);
+        let task = ticks.spawn_periodic(Duration::from_secs(1), |n| *n += 1);
+        // The first tick fires immediately, then one per period.
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        task.abort();
+        let _ = task.await;
+        assert_eq!(ticks.get(), 3);
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(ticks.get(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_debounced_setter_applies_last_value_once() {
+        use std::time::Duration;
+
+        let value = A::new(0);
+        let writes = A::new(0);
+        let _watcher = {
+            let (value, writes) = (value.clone(), writes.clone());
+            tokio::spawn(async move {
+                let mut last = 0;
+                loop {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                    let current = value.get();
+                    if current != last {
+                        writes.update(|w| *w += 1);
+                        last = current;
+                    }
+                }
+            })
+        };
+
+        let set = value.debounced_setter(Duration::from_millis(100));
+        for n in 1..=3 {
+            set(n);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(value.get(), 0);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(value.get(), 3);
+        assert_eq!(writes.get(), 1);
+    }
+
+    #[test]
+    fn test_take_if_gates_on_predicate() {
+        #[derive(Debug, PartialEq)]
+        struct Job {
+            owner: u32,
+        }
+
+        let queue = A::new(Some(Job { owner: 1 }));
+        assert_eq!(queue.take_if(|job| job.owner == 2), None);
+        assert!(queue.read().is_some());
+        assert_eq!(queue.take_if(|job| job.owner == 1), Some(Job { owner: 1 }));
+        assert!(queue.read().is_none());
+        assert_eq!(queue.take_if(|_| true), None);
+
+        let count = A::new(3);
+        assert_eq!(count.replace_default_if(|n| *n > 5), None);
+        assert_eq!(count.replace_default_if(|n| *n == 3), Some(3));
+        assert_eq!(count.get(), 0);
+    }
+
+    #[test]
+    fn test_group_broadcast_reaches_all_members() {
+        let group = Group::new();
+        let handles: Vec<_> = (0..3).map(|i| A::new(vec![i])).collect();
+        for handle in &handles {
+            group.register(handle.clone());
+        }
+        assert_eq!(group.len(), 3);
+
+        group.broadcast(|v| v.push(100));
+        for (i, handle) in handles.iter().enumerate() {
+            assert_eq!(handle.get(), vec![i as i32, 100]);
+        }
+        assert_eq!(
+            group.snapshot_all(),
+            vec![vec![0, 100], vec![1, 100], vec![2, 100]]
+        );
+    }
+
+    #[test]
+    fn test_fetch_max_tracks_high_water_mark() {
+        let peak = A::new(0);
+        let previous: Vec<_> = [3, 1, 7, 7, 2].iter().map(|&n| peak.fetch_max(n)).collect();
+        assert_eq!(previous, vec![0, 3, 3, 7, 7]);
+        assert_eq!(peak.get(), 7);
+
+        let low = A::new(10);
+        assert_eq!(low.fetch_min(4), 10);
+        assert_eq!(low.fetch_min(6), 4);
+        assert_eq!(low.get(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_tspawn_call_passes_captures_in_order() {
+        async fn transfer(
+            amount: A<u32>,
+            mut from: WriteGuard<u32>,
+            mut to: WriteGuard<u32>,
+        ) -> u32 {
+            let amount = amount.get();
+            *from -= amount;
+            *to += amount;
+            *to
+        }
+
+        let amount = A::new(30);
+        let from = A::new(100);
+        let to = A::new(5);
+
+        let result = tspawn_call!(transfer, amount, mut from, mut to)
+            .await
+            .unwrap();
+        assert_eq!(result, 35);
+        assert_eq!(from.get(), 70);
+        assert_eq!(to.get(), 35);
+    }
+
+    #[test]
+    fn test_read_chunks_splits_buffer() {
+        let buffer = A::new((0u8..10).collect::<Vec<_>>());
+        let chunks = buffer.read_chunks(4);
+        assert_eq!(
+            chunks.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![4, 4, 2]
+        );
+        assert_eq!(chunks.concat(), buffer.get());
+        // The lock is released once the chunks are returned.
+        assert!(buffer.try_write_arc().is_some());
+    }
+
+    #[test]
+    fn test_with_read_timeout() {
+        use std::time::Duration;
+
+        let data = A::new(String::from("ready"));
+        let len = data.with_read_timeout(Duration::from_millis(50), |s| s.len());
+        assert_eq!(len, Ok(5));
+
+        let guard = data.write();
+        let blocked = data.with_read_timeout(Duration::from_millis(20), |s| s.len());
+        assert_eq!(blocked, Err(TimedOut));
+        drop(guard);
+    }
+
+    #[test]
+    fn test_reinit_tears_down_after_releasing_lock() {
+        use std::time::Duration;
+
+        let resource = A::new(String::from("v1"));
+        let reader = resource.clone();
+        let mut torn_down = None;
+        resource.reinit(String::from("v2"), |old| {
+            let seen = std::thread::spawn(move || {
+                reader.with_read_timeout(Duration::from_secs(1), |s| s.clone())
+            })
+            .join()
+            .unwrap();
+            assert_eq!(seen, Ok(String::from("v2")));
+            torn_down = Some(old);
+        });
+        assert_eq!(torn_down.as_deref(), Some("v1"));
+    }
+
+    #[test]
+    fn test_clone_if() {
+        let data = A::new(1);
+        assert!(data.clone_if(false).is_none());
+        let cloned = data.clone_if(true).unwrap();
+        assert_eq!(cloned.as_ptr(), data.as_ptr());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_pointer_get_and_set() {
+        use serde_json::json;
+
+        let config = A::new(json!({ "server": { "host": "localhost", "port": 8080 } }));
+        assert_eq!(config.json_get("/server/port"), Some(json!(8080)));
+
+        assert!(config.json_set("/server/port", json!(9000)));
+        assert_eq!(config.json_get("/server/port"), Some(json!(9000)));
+        assert_eq!(config.json_get("/server/host"), Some(json!("localhost")));
+
+        assert!(!config.json_set("/server/tls/enabled", json!(true)));
+        assert_eq!(config.json_get("/server/tls"), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_spawn_bounded_throttles_producer() {
+        use std::time::Duration;
+
+        let data = A::new(0);
+        let (updater, task) = data.spawn_bounded(2);
+
+        // Stall the draining task so queued updates cannot be applied.
+        let guard = data.write();
+        let mut queued = 0;
+        while tokio::time::timeout(Duration::from_millis(50), updater.update(|n| *n += 1))
+            .await
+            .is_ok()
+        {
+            queued += 1;
+            assert!(queued <= 3, "producer was not throttled");
+        }
+        // The task may have dequeued one update before blocking on the lock.
+        assert!(queued >= 2);
+        drop(guard);
+
+        drop(updater);
+        task.await.unwrap();
+        assert_eq!(data.get(), queued);
+    }
+
+    #[test]
+    fn test_from_rwlock() {
+        use parking_lot::RwLock;
+
+        let data = A::from_rwlock(RwLock::new(1));
+        data.update(|x| *x += 1);
+        assert_eq!(data.get(), 2);
+
+        let from_lock: A<i32> = RwLock::new(3).into();
+        assert_eq!(from_lock.get(), 3);
+
+        let from_box = A::from(Box::new(RwLock::new(4)));
+        assert_eq!(*from_box.read(), 4);
+    }
+
+    #[test]
+    fn test_map_collect() {
+        let lengths = A::new(vec![1, 10, 100]);
+        let labels: Vec<String> = lengths.map_collect(|n| format!("{n}m"));
+        assert_eq!(labels, vec!["1m", "10m", "100m"]);
+
+        let doubled: std::collections::BTreeSet<i32> = lengths.map_collect(|n| n * 2);
+        assert_eq!(doubled.into_iter().collect::<Vec<_>>(), vec![2, 20, 200]);
+    }
+
+    #[test]
+    fn test_acquire_two_opposite_orders_do_not_deadlock() {
+        let left = A::new(0);
+        let right = A::new(0);
+
+        let workers: Vec<_> = [false, true]
+            .into_iter()
+            .map(|reversed| {
+                let (left, right) = (left.clone(), right.clone());
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        if reversed {
+                            let (mut r, mut l) = A::acquire_two(&right, &left);
+                            *r += 1;
+                            *l -= 1;
+                        } else {
+                            let (mut l, mut r) = A::acquire_two(&left, &right);
+                            *l += 1;
+                            *r -= 1;
+                        }
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!((left.get(), right.get()), (0, 0));
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_set_notify_skips_equal_values() {
+        use std::time::Duration;
+        use tokio_stream::StreamExt;
+
+        let data = A::new(1);
+        let mut changes = data.changes();
+
+        assert!(!data.set_notify(1));
+        assert!(data.set_notify(2));
+        assert!(!data.set_notify(2));
+
+        assert_eq!(changes.next().await, Some(2));
+        let extra = tokio::time::timeout(Duration::from_millis(20), changes.next()).await;
+        assert!(extra.is_err(), "no-op writes must not notify");
+    }
+
+    #[tokio::test]
+    async fn test_with_slice_reads_without_cloning() {
+        let samples = A::new((1..=100).collect::<Vec<u64>>());
+        let ptr = samples.read().as_ptr() as usize;
+
+        let sum = samples.with_slice(|s| {
+            assert_eq!(s.as_ptr() as usize, ptr);
+            s.iter().sum::<u64>()
+        });
+        assert_eq!(sum, 5050);
+
+        let slice = samples.read_slice();
+        let max = tokio::spawn(async move { slice.iter().copied().max() });
+        assert_eq!(max.await.unwrap(), Some(100));
+    }
+
+    #[tokio::test]
+    async fn test_map_inner_async_holds_no_lock() {
+        let port = A::new(8080u32);
+        let probe = port.clone();
+        let address = port
+            .map_inner_async(|port| async move {
+                tokio::task::yield_now().await;
+                assert!(probe.try_write_arc().is_some(), "lock held across await");
+                format!("localhost:{port}")
+            })
+            .await;
+        assert_eq!(address.get(), "localhost:8080");
+        assert_ne!(address.as_ptr() as usize, port.as_ptr() as usize);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_into_actor_applies_all_updates() {
+        let count = A::new(0);
+        let (client, actor) = count.clone().into_actor();
+
+        let producers: Vec<_> = (0..10)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move {
+                    for _ in 0..10 {
+                        assert!(client.update(|n| *n += 1));
+                    }
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.await.unwrap();
+        }
+        drop(client);
+        actor.await.unwrap();
+        assert_eq!(count.get(), 100);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test(start_paused = true)]
+    async fn test_poll_changes_emits_only_on_change() {
+        use std::time::Duration;
+        use tokio_stream::StreamExt;
+
+        let data = A::new(0);
+        let changes = data.poll_changes(Duration::from_millis(10));
+        let writer = {
+            let data = data.clone();
+            tokio::spawn(async move {
+                // Each value is held for several poll periods.
+                for value in [0, 1, 1, 2, 2, 2, 3] {
+                    data.set(value);
+                    tokio::time::sleep(Duration::from_millis(35)).await;
+                }
+            })
+        };
+        let seen: Vec<_> = changes.take(4).collect().await;
+        writer.await.unwrap();
+        assert_eq!(seen, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_mutate_then_reads_own_write() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let data = A::new(0u64);
+        let stop = Arc::new(AtomicBool::new(false));
+        let writers: Vec<_> = (0..2)
+            .map(|_| {
+                let (data, stop) = (data.clone(), Arc::clone(&stop));
+                std::thread::spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        data.set(0);
+                    }
+                })
+            })
+            .collect();
+
+        for n in 1..=1000 {
+            let seen = data.mutate_then(|x| *x = n, |x| *x);
+            assert_eq!(seen, n);
+        }
+        stop.store(true, Ordering::Relaxed);
+        for writer in writers {
+            writer.join().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tspawn_with_local_sets_task_local() {
+        tokio::task_local! {
+            static TENANT: &'static str;
+        }
+
+        let usage = A::new(std::collections::HashMap::new());
+        let cost = A::new(5);
+        tspawn_with_local!(TENANT = "acme", mut usage, ref cost, {
+            usage.insert(TENANT.get(), *cost);
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(usage.read().get("acme"), Some(&5));
+        assert!(TENANT.try_with(|_| ()).is_err());
+    }
+
+    #[test]
+    fn test_read_leased_reports_matched_events() {
+        use std::sync::{Arc, Mutex};
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let data = A::new(String::from("config"));
+
+        let leases: Vec<u64> = (0..3)
+            .map(|_| {
+                let log = Arc::clone(&events);
+                let guard = data.read_leased(move |event| log.lock().unwrap().push(event));
+                assert_eq!(guard.len(), 6);
+                guard.lease_id()
+            })
+            .collect();
+
+        let expected: Vec<_> = leases
+            .iter()
+            .flat_map(|&id| [LeaseEvent::Acquired(id), LeaseEvent::Released(id)])
+            .collect();
+        assert_eq!(*events.lock().unwrap(), expected);
+        assert!(leases[0] < leases[1] && leases[1] < leases[2]);
+    }
+
+    #[test]
+    fn test_nested_map_inner_handles_lock_independently() {
+        let rooms = A::nested_map([("lobby", vec!["ann"]), ("games", vec![])]);
+        let lobby = rooms.read()["lobby"].clone();
+
+        // Holding the outer map for reading does not block inner writes.
+        let outer = rooms.read();
+        let mut members = lobby.write();
+        members.push("bob");
+        drop(members);
+        assert_eq!(outer["lobby"].get(), vec!["ann", "bob"]);
+        assert!(outer["games"].read().is_empty());
+        drop(outer);
+
+        assert!(rooms.try_write_arc().is_some());
+    }
+
+    #[cfg(feature = "await_check")]
+    #[tokio::test]
+    async fn test_await_check_reports_guard_held_across_await() {
+        let held_across = A::new(0);
+        let released_before = A::new(0);
+
+        tspawn!(mut held_across, {
+            tokio::task::yield_now().await;
+            *held_across += 1;
+        })
+        .await
+        .unwrap();
+
+        tspawn!(mut released_before, {
+            *released_before += 1;
+            drop(released_before);
+            tokio::task::yield_now().await;
+        })
+        .await
+        .unwrap();
+
+        let reported = crate::held::REPORTED.lock();
+        assert!(reported.contains(&"held_across"));
+        assert!(!reported.contains(&"released_before"));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_update_doubles_every_element() {
+        let values = A::new((0..100_000).collect::<Vec<i64>>());
+        values.par_update(|v| *v *= 2);
+        let values = values.read();
+        assert!(values.iter().enumerate().all(|(i, &v)| v == 2 * i as i64));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode_round_trip() {
+        use serde::{Deserialize, Serialize};
+        use std::collections::BTreeMap;
+
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        struct GameState {
+            round: u32,
+            active: bool,
+            scores: BTreeMap<String, i64>,
+        }
+
+        let state = A::new(GameState {
+            round: 3,
+            active: true,
+            scores: BTreeMap::from([("alice".to_string(), 120), ("bob".to_string(), -5)]),
+        });
+        let bytes = state.to_bytes().unwrap();
+        let restored = A::<GameState>::from_bytes(&bytes).unwrap();
+
+        assert_eq!(restored.get(), state.get());
+        assert_ne!(restored.as_ptr() as usize, state.as_ptr() as usize);
+        assert!(A::<GameState>::from_bytes(&bytes[..2]).is_err());
+    }
+
+    #[test]
+    fn test_upgrade_times_out_behind_reader() {
+        use parking_lot::ArcRwLockUpgradableReadGuard;
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let data = A::new(1);
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let reader = {
+            let data = data.clone();
+            std::thread::spawn(move || {
+                let _guard = data.read();
+                locked_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+            })
+        };
+        locked_rx.recv().unwrap();
+
+        let guard = data
+            .try_upgradable_read_for(Duration::from_millis(50))
+            .expect("upgradable read coexists with readers");
+        let upgrade =
+            ArcRwLockUpgradableReadGuard::try_upgrade_for(guard, Duration::from_millis(20));
+        let Err(guard) = upgrade else {
+            panic!("upgrade must time out while the reader holds the lock");
+        };
+        assert_eq!(*guard, 1);
+
+        release_tx.send(()).unwrap();
+        reader.join().unwrap();
+        let mut write =
+            ArcRwLockUpgradableReadGuard::try_upgrade_for(guard, Duration::from_millis(50))
+                .ok()
+                .unwrap();
+        *write += 1;
+        drop(write);
+        assert_eq!(data.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_consumer_handles_all_items() {
+        use std::collections::VecDeque;
+        use std::time::Duration;
+
+        let queue = A::new(VecDeque::new());
+        let handled = A::new(Vec::new());
+        for job in 0..5 {
+            queue.push_back(job);
+        }
+        let worker = {
+            let handled = handled.clone();
+            queue.spawn_consumer(move |job| handled.update(|h| h.push(job)))
+        };
+        for job in 5..10 {
+            queue.push_back(job);
+        }
+
+        let wait_for = |count: usize| {
+            let handled = handled.clone();
+            tokio::time::timeout(Duration::from_secs(5), async move {
+                while handled.read().len() < count {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            })
+        };
+        wait_for(10).await.unwrap();
+
+        // An idle worker still picks up items pushed through any write path.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        queue.update(|q| q.push_back(10));
+        wait_for(11).await.unwrap();
+        worker.abort();
+
+        assert_eq!(handled.get(), (0..11).collect::<Vec<_>>());
+        assert_eq!(queue.pop_front(), None);
+    }
+
+    #[cfg(feature = "history")]
+    #[test]
+    fn test_history_keeps_last_values_in_order() {
+        let counter = HistoryA::new(0, 3);
+        assert!(counter.history().is_empty());
+
+        counter.set(1);
+        counter.update(|n| *n += 10);
+        assert_eq!(counter.replace(20), 11);
+        counter.clone().set(30);
+
+        assert_eq!(counter.get(), 30);
+        assert_eq!(counter.history(), vec![11, 20, 30]);
+
+        counter.handle().set(99);
+        assert_eq!(counter.history(), vec![11, 20, 30]);
+    }
+
+    #[test]
+    fn test_priority_writer_is_not_starved() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        const READERS: usize = 4;
+        let data = PriorityA::new(0);
+        let reads = Arc::new(AtomicUsize::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let readers: Vec<_> = (0..READERS)
+            .map(|_| {
+                let (data, reads, stop) = (data.clone(), Arc::clone(&reads), Arc::clone(&stop));
+                std::thread::spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        let guard = data.read();
+                        reads.fetch_add(1, Ordering::Relaxed);
+                        std::thread::sleep(Duration::from_micros(200));
+                        drop(guard);
+                    }
+                })
+            })
+            .collect();
+
+        while reads.load(Ordering::Relaxed) < 100 {
+            std::thread::yield_now();
+        }
+        for n in 1..=10 {
+            let before = reads.load(Ordering::Relaxed);
+            data.set(n);
+            let during = reads.load(Ordering::Relaxed) - before;
+            // Only readers already past the gate may acquire before the writer.
+            assert!(during <= 2 * READERS, "writer waited for {during} reads");
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(data.get(), 10);
+    }
+
+    #[test]
+    fn test_reader_fn_reflects_later_mutations() {
+        let level = A::new(1);
+        let current = level.reader_fn();
+        let copy = current.clone();
+        assert_eq!(current(), 1);
+
+        level.update(|l| *l += 1);
+        assert_eq!(current(), 2);
+        assert_eq!(std::thread::spawn(copy).join().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_rebind_leaves_old_clones_behind() {
+        let mut handle = A::new(1);
+        let old_clone = handle.clone();
+        let other = A::new(100);
+
+        handle.rebind(&other);
+        other.set(200);
+        assert_eq!(handle.get(), 200);
+        assert_eq!(old_clone.get(), 1);
+
+        handle.set(300);
+        assert_eq!(other.get(), 300);
+        assert_eq!(old_clone.get(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_update_async_under_contention() {
+        let counter = A::new(0u32);
+        let tasks: Vec<_> = (0..50)
+            .map(|_| {
+                let counter = counter.clone();
+                tokio::spawn(async move {
+                    for _ in 0..20 {
+                        counter.update_async(|n| *n += 1).await;
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(counter.get(), 1000);
+    }
+
+    #[test]
+    fn test_trait_object_handles() {
+        trait State: Send + Sync {
+            fn tick(&mut self);
+            fn describe(&self) -> String;
+        }
+
+        struct Counter(u32);
+        impl State for Counter {
+            fn tick(&mut self) {
+                self.0 += 1;
+            }
+            fn describe(&self) -> String {
+                format!("counter={}", self.0)
+            }
+        }
+
+        struct Toggle(bool);
+        impl State for Toggle {
+            fn tick(&mut self) {
+                self.0 = !self.0;
+            }
+            fn describe(&self) -> String {
+                format!("toggle={}", self.0)
+            }
+        }
+
+        let states: Vec<A<Box<dyn State>>> = vec![
+            A::new(Box::new(Counter(0))),
+            A::new(Box::new(Toggle(false))),
+        ];
+        let shared = states[0].clone();
+
+        for state in &states {
+            state.update(|s| s.tick());
+        }
+        shared.update(|s| s.tick());
+
+        let described: Vec<String> = states.iter().map(|s| s.read().describe()).collect();
+        assert_eq!(described, vec!["counter=2", "toggle=true"]);
+    }
+
+    #[cfg(feature = "labeled")]
+    #[test]
+    fn test_label_added_to_panic_payload() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let orders = A::new(vec![1u32]).with_label("orders");
+        let payload = catch_unwind(AssertUnwindSafe(|| {
+            orders.update(|o| {
+                let _ = o[5];
+            })
+        }))
+        .unwrap_err();
+        let message = payload.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("[orders] "), "{message}");
+        assert!(message.contains("index out of bounds"), "{message}");
+
+        // The lock was released during unwinding.
+        orders.update(|o| o.push(2));
+        assert_eq!(orders.get(), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_if_readable_skips_when_write_locked() {
+        let data = A::new(String::from("ready"));
+
+        let guard = data.write();
+        assert!(data.spawn_if_readable(|s| async move { s.len() }).is_none());
+        drop(guard);
+
+        let task = data.spawn_if_readable(|s| async move { s.len() }).unwrap();
+        assert_eq!(task.await.unwrap(), 5);
+    }
+
+    #[test]
+    fn test_merge_reversed_orders_do_not_deadlock() {
+        let a = A::new(vec![0u32; 2]);
+        let b = A::new(vec![0u32; 2]);
+
+        let workers: Vec<_> = [(a.clone(), b.clone(), 0), (b.clone(), a.clone(), 1)]
+            .into_iter()
+            .map(|(into, from, slot)| {
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        into.merge(&from, |mine, theirs| {
+                            mine[slot] = mine[slot].max(theirs[slot]) + 1;
+                        });
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert!(a.get()[0] >= 1000);
+        assert!(b.get()[1] >= 1000);
+
+        let counter_a = A::new(3);
+        let counter_b = A::new(4);
+        counter_a.merge(&counter_b, |mine, theirs| *mine += theirs);
+        assert_eq!((counter_a.get(), counter_b.get()), (7, 4));
+    }
+
+    #[test]
+    fn test_btreemap_helpers() {
+        use std::collections::BTreeMap;
+
+        let schedule = A::new(BTreeMap::new());
+        for (hour, task) in [
+            (9, "standup"),
+            (13, "review"),
+            (11, "deploy"),
+            (16, "retro"),
+        ] {
+            assert_eq!(schedule.insert(hour, task), None);
+        }
+        assert_eq!(schedule.insert(13, "design review"), Some("review"));
+        assert_eq!(schedule.remove(&16), Some("retro"));
+        assert_eq!(schedule.remove(&16), None);
+
+        assert_eq!(schedule.get_cloned(&11), Some("deploy"));
+        assert_eq!(
+            schedule.range_cloned(10..=13),
+            vec![(11, "deploy"), (13, "design review")]
+        );
+        assert_eq!(schedule.first_key_value_cloned(), Some((9, "standup")));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_map_reduce_matches_sequential_fold() {
+        let handles: Vec<_> = (1..=20)
+            .map(|n| A::new((0..n).collect::<Vec<u64>>()))
+            .collect();
+
+        let parallel = A::map_reduce(&handles, |v| v.iter().sum::<u64>(), |a, b| a + b).await;
+        let sequential = A::fold(&handles, 0, |acc, v| acc + v.iter().sum::<u64>());
+        assert_eq!(parallel, Some(sequential));
+
+        let none = A::<Vec<u64>>::map_reduce(&[], |v| v.len(), |a, b| a + b).await;
+        assert_eq!(none, None);
+    }
+
+    #[cfg(feature = "observers")]
+    #[test]
+    fn test_write_hook_sees_every_mutation_in_order() {
+        let seen = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let log = std::sync::Arc::clone(&seen);
+        let data = A::new(0).with_write_hook(move |value| log.lock().push(*value));
+
+        data.set(1);
+        data.update(|x| *x += 10);
+        assert_eq!(data.replace_default(), 11);
+        data.clone().update(|x| *x += 5);
+        *data.write() = 99;
+
+        assert_eq!(*seen.lock(), vec![1, 11, 0, 5]);
+    }
+
+    #[test]
+    fn test_with_write_timed_reports_hold_time() {
+        let data = A::new(0);
+        let (result, held) = data.with_write_timed(|x| {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            *x += 1;
+            "done"
+        });
+
+        assert_eq!(result, "done");
+        assert!(held >= std::time::Duration::from_millis(20));
+        assert_eq!(data.get(), 1);
+    }
+
+    #[test]
+    fn test_update_batch_applies_atomically() {
+        let data = A::new((0, 0, 0));
+        let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let reader = {
+            let (data, stop) = (data.clone(), stop.clone());
+            std::thread::spawn(move || {
+                while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                    let (a, b, c) = data.get();
+                    assert!(a == b && b == c, "saw partial batch ({a}, {b}, {c})");
+                }
+            })
+        };
+
+        for _ in 0..1000 {
+            let mut batch = data.batched();
+            batch.push(|v| v.0 += 1);
+            batch.push(|v| v.1 += 1);
+            batch.push(|v| v.2 += 1);
+            assert_eq!(batch.len(), 3);
+            batch.commit();
+        }
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        reader.join().unwrap();
+
+        assert_eq!(data.get(), (1000, 1000, 1000));
+    }
+
+    #[test]
+    fn test_drain_while_takes_leading_ready_items() {
+        let jobs = A::new(vec![(1, true), (2, true), (3, false), (4, true)]);
+
+        let ready = jobs.drain_while(|&(_, ready)| ready);
+        assert_eq!(ready, vec![(1, true), (2, true)]);
+        assert_eq!(jobs.get(), vec![(3, false), (4, true)]);
+
+        assert!(jobs.drain_while(|&(_, ready)| ready).is_empty());
+        assert_eq!(jobs.read().len(), 2);
+    }
+
+    #[test]
+    fn test_fork_is_independent_of_original() {
+        let original = A::new(vec![1, 2, 3]);
+        let fork = original.fork(|v| v.push(4));
+
+        assert_eq!(fork.get(), vec![1, 2, 3, 4]);
+        assert_eq!(original.get(), vec![1, 2, 3]);
+
+        fork.update(|v| v.clear());
+        original.update(|v| v.push(9));
+        assert!(fork.get().is_empty());
+        assert_eq!(original.get(), vec![1, 2, 3, 9]);
+    }
+
+    #[test]
+    fn test_try_read_recursive_nests_past_waiting_writer() {
+        let data = A::new(1);
+        let outer = data.try_read_recursive().unwrap();
+
+        let writer = {
+            let data = data.clone();
+            std::thread::spawn(move || {
+                let mut guard = data.write();
+                *guard = 2;
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            })
+        };
+        // Wait until the writer is queued and blocking ordinary reads.
+        while data.try_read_arc().is_some() {
+            std::thread::yield_now();
+        }
+
+        let inner = data
+            .try_read_recursive()
+            .expect("nested read past waiting writer");
+        assert_eq!(*inner, 1);
+        drop(inner);
+        drop(outer);
+
+        // Once the writer holds the lock, recursive reads fail too.
+        while data.try_read_recursive().is_some_and(|v| *v == 1) {
+            std::thread::yield_now();
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let during_write = data.try_read_recursive().is_none();
+        writer.join().unwrap();
+        assert!(during_write);
+        assert_eq!(*data.try_read_recursive().unwrap(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_spawn_flusher_coalesces_burst() {
+        let buffer = A::new(Vec::new());
+        let flushes = A::new(Vec::new());
+        let log = flushes.clone();
+        let (task, mark_dirty) = buffer
+            .spawn_flusher(std::time::Duration::from_millis(50), move |b: &Vec<i32>| {
+                log.update(|l| l.push(b.len()))
+            });
+
+        for n in 0..5 {
+            buffer.update(|b| b.push(n));
+            mark_dirty();
+            tokio::time::advance(std::time::Duration::from_millis(5)).await;
+        }
+        assert!(flushes.get().is_empty());
+
+        tokio::time::advance(std::time::Duration::from_millis(50)).await;
+        tokio::task::yield_now().await;
+        assert_eq!(flushes.get(), vec![5]);
+
+        drop(mark_dirty);
+        task.await.unwrap();
+        assert_eq!(flushes.get(), vec![5]);
+    }
+
+    #[cfg(feature = "lock_order")]
+    #[test]
+    fn test_lock_order_inversion_panics() {
+        let low = A::new(1).with_level(1);
+        let high = A::new(2).with_level(2);
+
+        low.update(|l| high.update(|h| *h += *l));
+        assert_eq!(high.get(), 3);
+
+        let inverted = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            high.update(|_| {
+                let _ = *low.read();
+            })
+        }));
+        let message = inverted.unwrap_err();
+        assert!(message
+            .downcast_ref::<String>()
+            .unwrap()
+            .contains("lock-order inversion"));
+
+        // The held levels unwound with the panic, so in-order locking still works.
+        low.update(|l| high.update(|h| *h = *l));
+        assert_eq!(high.get(), 1);
+    }
+
+    #[cfg(feature = "lock_order")]
+    #[test]
+    fn test_lock_order_covers_every_helper() {
+        let low = A::new(1).with_level(1);
+        let high = A::new(vec![2]).with_level(2);
+        let inverts =
+            |f: &dyn Fn()| std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).is_err();
+
+        assert!(inverts(&|| high.update(|_| {
+            low.get();
+        })));
+        assert!(inverts(&|| {
+            high.map_owned(|_| low.get());
+        }));
+        assert!(inverts(&|| {
+            high.update_when(|h| &h[0], &2, |_| low.set(5));
+        }));
+        assert!(inverts(&|| high.mutate_then(|_| low.set(5), |_| ())));
+        assert!(inverts(&|| high.retain(|_| low.get() > 0)));
+        assert_eq!(low.get(), 1);
+        assert_eq!(high.get(), vec![2]);
+
+        // The same nesting in hierarchy order is allowed.
+        low.update(|l| *l += high.len() as i32);
+        A::with_both_mut(&high, &low, |h, l| h.push(*l));
+        assert_eq!(high.get(), vec![2, 2]);
+    }
+
+    #[cfg(feature = "lock_order")]
+    #[tokio::test]
+    async fn test_lock_order_tracks_guards_and_captures() {
+        let low = A::new(1);
+        let early_clone = low.clone();
+        let low = low.with_level(1);
+        let high = A::new(2).with_level(2);
+        assert_eq!(early_clone.level(), Some(1));
+
+        let inverted = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _high = high.write();
+            let _low = early_clone.write();
+        }));
+        assert!(inverted.is_err());
+
+        // A dropped guard no longer counts as held.
+        drop(high.read());
+        low.update(|l| *l += 1);
+
+        // A capture counts as held by its task, not by the spawning thread.
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+        let inner_low = low.clone();
+        let task = tspawn!(mut high, {
+            released.await.unwrap();
+            *high += *inner_low.read();
+        });
+        low.update(|l| *l += 1);
+        release.send(()).unwrap();
+        assert!(task.await.unwrap_err().is_panic());
+        assert_eq!(low.get(), 3);
+    }
+
+    #[cfg(feature = "arc_swap")]
+    #[test]
+    fn test_swappable_backing_is_shared_by_clones() {
+        let config = SwappableA::new(1);
+        let clones: Vec<_> = (0..3).map(|_| config.clone()).collect();
+        let held = config.read();
+
+        let fresh = std::sync::Arc::new(parking_lot::RwLock::new(10));
+        let old = config.swap_backing(fresh.clone());
+        assert_eq!(*old.read(), 1);
+        // Guards taken before the swap still see the old storage.
+        assert_eq!(*held, 1);
+
+        for clone in &clones {
+            assert_eq!(clone.get(), 10);
+            assert!(std::sync::Arc::ptr_eq(&clone.backing(), &fresh));
+        }
+        clones[0].update(|v| *v += 1);
+        assert_eq!(*fresh.read(), 11);
+        assert_eq!(config.get(), 11);
+    }
+
+    #[test]
+    fn test_read_three_sees_consistent_snapshot() {
+        let a = A::new(0u64);
+        let b = A::new(0u64);
+        let c = A::new(0u64);
+
+        let writer = {
+            let (a, b, c) = (a.clone(), b.clone(), c.clone());
+            std::thread::spawn(move || {
+                for _ in 0..2000 {
+                    let mut handles = [&a, &b, &c];
+                    handles.sort_by_key(|h| h.lock_addr());
+                    let mut guards: Vec<_> = handles.iter().map(|h| h.write()).collect();
+                    for guard in &mut guards {
+                        **guard += 1;
+                    }
+                }
+            })
+        };
+
+        loop {
+            let (x, y, z) = A::read_three(&a, &b, &c, |x, y, z| (*x, *y, *z));
+            assert!(x == y && y == z, "torn read ({x}, {y}, {z})");
+            if x == 2000 {
+                break;
+            }
+        }
+        writer.join().unwrap();
+
+        let twice = read_all!(a, a, c => |first, second, third| first + second + third);
+        assert_eq!(twice, 6000);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_spawn_on_change_runs_per_write() {
+        let data = A::new(0);
+        let seen = A::new(Vec::new());
+        let log = seen.clone();
+        let task = data.spawn_on_change(move |value| log.update(|l| l.push(*value)));
+
+        data.set(1);
+        data.update(|x| *x += 1);
+        drop(data);
+        task.await.unwrap();
+
+        assert_eq!(seen.get(), vec![1, 2]);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test(start_paused = true)]
+    async fn test_write_stream_yields_guards_in_sequence() {
+        use tokio_stream::StreamExt;
+
+        let data = A::new(0);
+        let mut guards = data.write_stream();
+
+        let mut first = guards.next().await.unwrap();
+        *first += 1;
+        drop(first);
+
+        let mut second = guards.next().await.unwrap();
+        assert_eq!(*second, 1);
+        *second += 1;
+        assert!(data.try_read_arc().is_none());
+        drop(second);
+        assert_eq!(data.get(), 2);
+
+        let held = data.try_write_arc().unwrap();
+        let waiting = tokio::time::timeout(std::time::Duration::from_millis(5), guards.next());
+        assert!(waiting.await.is_err());
+        drop(held);
+        assert_eq!(*guards.next().await.unwrap(), 2);
+    }
+
+    #[test]
+    fn test_restore_reverts_to_checkpoint() {
+        let state = A::new(vec![1]);
+        let saved = state.checkpoint();
+
+        state.update(|v| v.push(2));
+        state.set(vec![9, 9]);
+        assert_eq!(saved.value(), &vec![1]);
+
+        state.restore(&saved);
+        assert_eq!(state.get(), vec![1]);
+
+        state.update(|v| v.clear());
+        state.restore(&saved);
+        assert_eq!(state.get(), saved.into_inner());
+    }
+
+    #[test]
+    fn test_update_at_in_range() {
+        let data = A::new(vec![1, 2, 3]);
+        assert!(data.update_at(2, |x| *x *= 10));
+        assert_eq!(data.get(), vec![1, 2, 30]);
+    }
+
+    #[test]
+    fn test_update_at_out_of_range_is_noop() {
+        let data = A::new(vec![1, 2, 3]);
+        let mut called = false;
+        assert!(!data.update_at(3, |_| called = true));
+        assert!(!called);
+        assert_eq!(data.get(), vec![1, 2, 3]);
+    }
+
+    #[cfg(feature = "arc_swap")]
+    #[test]
+    fn test_arc_swap_compare_and_swap() {
+        use std::sync::Arc;
+
+        let config = ArcSwapA::new(1);
+        let original = config.load();
+
+        let next = Arc::new(2);
+        let previous = config.compare_and_swap(&original, Arc::clone(&next));
+        assert!(Arc::ptr_eq(&previous, &original));
+        assert!(Arc::ptr_eq(&config.load(), &next));
+
+        // A stale snapshot no longer matches, even with an equal value.
+        let stale = Arc::new(2);
+        let previous = config.compare_and_swap(&stale, Arc::new(3));
+        assert!(Arc::ptr_eq(&previous, &next));
+        assert!(Arc::ptr_eq(&config.clone().load(), &next));
+    }
+
+    #[tokio::test]
+    async fn test_spawn_guarded_aborts_on_drop() {
+        let data = A::new(0);
+        let slow = |data: A<i32>| async move {
+            tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+            data.set(1);
+        };
+
+        drop(data.spawn_guarded(slow));
+        tokio::time::sleep(std::time::Duration::from_millis(80)).await;
+        assert_eq!(data.get(), 0);
+
+        let detached = data.spawn_guarded(slow).detach();
+        detached.await.unwrap();
+        assert_eq!(data.get(), 1);
+    }
+
+    #[test]
+    fn test_map_owned_extracts_sub_value() {
+        let data = A::new((String::from("name"), vec![1, 2, 3]));
+        let tail: Vec<i32> = data.map_owned(|(_, items)| items[1..].to_vec());
+        assert_eq!(tail, vec![2, 3]);
+
+        let name = data.map_owned(|(name, _)| std::borrow::Cow::<str>::Owned(name.to_uppercase()));
+        assert_eq!(name, "NAME");
+        assert_eq!(data.read().1.len(), 3);
+    }
+
+    #[test]
+    fn test_try_map_inner_success_and_failure() {
+        let valid = A::new(vec!["1", "2"]);
+        let invalid = A::new(vec!["3", "x"]);
+        let parse = |v: &Vec<&str>| {
+            v.iter()
+                .map(|s| s.parse::<i32>())
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let parsed = valid.try_map_inner(parse).unwrap();
+        assert_eq!(parsed.get(), vec![1, 2]);
+        assert!(invalid.try_map_inner(parse).is_err());
+        assert_eq!(invalid.get(), vec!["3", "x"]);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_runs_stages_in_order() {
+        let data = A::new(vec![1]);
+        let result = Pipeline::new(data.clone())
+            .stage(|d| async move {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                d.update(|v| v.push(2));
+            })
+            .stage(|d| async move {
+                let doubled: Vec<i32> = d.read().iter().map(|x| x * 2).collect();
+                d.set(doubled);
+            })
+            .run()
+            .await;
+
+        assert_eq!(data.get(), vec![2, 4]);
+        assert_eq!(result.get(), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_weak_cache_dedupes_live_handles() {
+        let cache = WeakCache::new();
+        let first = cache.get_or_create(1, || String::from("one"));
+        let second = cache.get_or_create(1, || String::from("other"));
+        assert_eq!(first.lock_addr(), second.lock_addr());
+        assert_eq!(second.get(), "one");
+
+        let other_key = cache.get_or_create(2, || String::from("two"));
+        assert_ne!(first.lock_addr(), other_key.lock_addr());
+
+        drop((first, second));
+        let recreated = cache.get_or_create(1, || String::from("fresh"));
+        assert_eq!(recreated.get(), "fresh");
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_query_channel_returns_derived_value() {
+        let scores = A::new(vec![4, 8, 15]);
+        let writer = scores.clone();
+        let (queries, task) = scores.query_channel();
+
+        let max = queries.query(|s| s.iter().copied().max()).await;
+        assert_eq!(max, Some(Some(15)));
+
+        writer.update(|s| s.push(16));
+        let len = queries.clone().query(|s| s.len()).await;
+        assert_eq!(len, Some(4));
+
+        drop(queries);
+        task.await.unwrap();
+    }
+
+    #[test]
+    fn test_retain_on_vec() {
+        let data = A::new(vec![1, 5, 2, 8, 3]);
+        data.retain(|&x| x < 4);
+        assert_eq!(data.get(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_retain_on_hash_map() {
+        use std::collections::HashMap;
+
+        let data = A::new(HashMap::from([("keep", 1), ("drop", -1), ("also", 2)]));
+        data.retain(|_, v| *v > 0);
+        let mut keys: Vec<_> = data.read().keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["also", "keep"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_spawn_permitted_bounds_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let data = A::new(0);
+        let limit = Arc::new(tokio::sync::Semaphore::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let mut tasks = Vec::new();
+        for _ in 0..8 {
+            let (running, peak) = (running.clone(), peak.clone());
+            let task = data
+                .spawn_permitted(limit.clone(), move |data| async move {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    data.update(|n| *n += 1);
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+                .await;
+            tasks.push(task);
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(data.get(), 8);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(limit.available_permits(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_log_value_serializes_lazily() {
+        let data = A::new(std::collections::BTreeMap::from([("a", 1)]));
+        let rendered = data.log_value();
+        data.update(|m| {
+            m.insert("b", 2);
+        });
+        assert_eq!(rendered.to_string(), r#"{"a":1,"b":2}"#);
+
+        let guard = data.write();
+        assert_eq!(data.log_value().to_string(), "<locked>");
+        drop(guard);
+    }
+
+    #[test]
+    fn test_drain_loses_no_samples_under_concurrent_pushes() {
+        let samples = A::new(Vec::new());
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let samples = samples.clone();
+                std::thread::spawn(move || {
+                    for i in 0..1000 {
+                        samples.update(|s| s.push(p * 1000 + i));
+                    }
+                })
+            })
+            .collect();
+
+        let mut collected = Vec::new();
+        while producers.iter().any(|p| !p.is_finished()) {
+            collected.extend(samples.drain());
+        }
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        collected.extend(samples.drain());
+
+        collected.sort();
+        assert_eq!(collected, (0..4000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_read2_sees_consistent_snapshot() {
+        let debit = A::new(0i64);
+        let credit = A::new(0i64);
+
+        let writer = {
+            let (debit, credit) = (debit.clone(), credit.clone());
+            std::thread::spawn(move || {
+                for _ in 0..2000 {
+                    let (mut d, mut c) = A::acquire_two(&debit, &credit);
+                    *d -= 1;
+                    *c += 1;
+                }
+            })
+        };
+
+        loop {
+            let (d, c) = A::read2_clone(&debit, &credit);
+            assert_eq!(d + c, 0, "torn read ({d}, {c})");
+            assert!(A::read2(&debit, &credit, |d, c| d + c == 0));
+            if c == 2000 {
+                break;
+            }
+        }
+        writer.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_spawn_supervised_restarts_after_panics() {
+        let attempts = A::new(0);
+        let worker = attempts.spawn_supervised(2, |attempts| async move {
+            attempts.update(|n| *n += 1);
+            if attempts.get() <= 2 {
+                panic!("transient failure");
+            }
+        });
+        worker.await.unwrap();
+        assert_eq!(attempts.get(), 3);
+
+        let failing = A::new(0);
+        let worker = failing.spawn_supervised(1, |failing| async move {
+            failing.update(|n| *n += 1);
+            panic!("permanent failure");
+        });
+        assert!(worker.await.unwrap_err().is_panic());
+        assert_eq!(failing.get(), 2);
+    }
+
+    #[test]
+    fn test_into_inner_value_timeout() {
+        use std::time::Duration;
+
+        let data = A::new(String::from("state"));
+        let clone = data.clone();
+        let dropper = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            drop(clone);
+        });
+        let value = data.into_inner_value_timeout(Duration::from_secs(2), Duration::from_millis(5));
+        assert_eq!(value.ok().as_deref(), Some("state"));
+        dropper.join().unwrap();
+
+        let data = A::new(1);
+        let leaked = data.clone();
+        let data = data
+            .into_inner_value_timeout(Duration::from_millis(30), Duration::from_millis(5))
+            .unwrap_err();
+        assert_eq!(data.handle_count(), 2);
+        drop(leaked);
+    }
+
+    #[test]
+    fn test_apply_events_replays_batch() {
+        #[derive(Clone, Default, Debug, PartialEq)]
+        struct GameState {
+            score: u32,
+            level: u32,
+            log: Vec<&'static str>,
+        }
+
+        enum GameEvent {
+            Scored(u32),
+            LevelUp,
+            Named(&'static str),
+        }
+
+        let state = A::new(GameState::default());
+        let applied = state.apply_events(
+            vec![
+                GameEvent::Scored(10),
+                GameEvent::LevelUp,
+                GameEvent::Named("boss"),
+            ],
+            |s, event| match event {
+                GameEvent::Scored(points) => s.score += points,
+                GameEvent::LevelUp => s.level += 1,
+                GameEvent::Named(name) => s.log.push(name),
+            },
+        );
+
+        assert_eq!(applied, 3);
+        assert_eq!(
+            state.get(),
+            GameState {
+                score: 10,
+                level: 1,
+                log: vec!["boss"]
+            }
+        );
+    }
+
+    #[test]
+    fn test_with_bytes_checksum_over_string() {
+        let text = A::new(String::from("tspawn"));
+        let checksum = text.with_bytes(|bytes| bytes.iter().map(|&b| u32::from(b)).sum::<u32>());
+        assert_eq!(checksum, "tspawn".bytes().map(u32::from).sum::<u32>());
+
+        let raw = A::new(vec![1u8, 2, 3]);
+        assert_eq!(raw.with_bytes(<[u8]>::len), 3);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_with_deadline_resets_on_timeout() {
+        let state = A::new(vec![1, 2, 3]);
+        state
+            .spawn_with_deadline(std::time::Duration::from_millis(20), |state| async move {
+                state.update(|v| v.push(4));
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                state.update(|v| v.push(5));
+            })
+            .await
+            .unwrap();
+        assert!(state.get().is_empty());
+
+        state
+            .spawn_with_deadline(std::time::Duration::from_secs(5), |state| async move {
+                state.set(vec![9]);
+            })
+            .await
+            .unwrap();
+        assert_eq!(state.get(), vec![9]);
+    }
+
+    #[test]
+    fn test_sort_by_key_on_structs() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Task {
+            priority: u8,
+            name: &'static str,
+        }
+
+        let tasks = A::new(vec![
+            Task {
+                priority: 3,
+                name: "deploy",
+            },
+            Task {
+                priority: 1,
+                name: "lint",
+            },
+            Task {
+                priority: 2,
+                name: "test",
+            },
+        ]);
+        tasks.sort_by_key(|t| t.priority);
+        let names: Vec<_> = tasks.read().iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["lint", "test", "deploy"]);
+
+        tasks.sort_by(|a, b| a.name.cmp(b.name));
+        assert_eq!(tasks.read()[0].name, "deploy");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_spawn_for_each_increments_shared_counter() {
+        let counter = A::new(0);
+        let tasks = counter.spawn_for_each(0..100, |counter, i| async move {
+            counter.update(|c| *c += 1);
+            i * 2
+        });
+        assert_eq!(tasks.len(), 100);
+
+        let mut results = Vec::new();
+        for task in tasks {
+            results.push(task.await.unwrap());
+        }
+        assert_eq!(counter.get(), 100);
+        assert_eq!(results, (0..100).map(|i| i * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_vec_summary_available_and_locked() {
+        let data = A::new(Vec::with_capacity(8));
+        data.update(|v| v.extend([1, 2]));
+        assert_eq!(data.len(), 2);
+        assert_eq!(data.summary(), "Vec(len=2, cap=8)");
+
+        let guard = data.write();
+        assert_eq!(data.summary(), "Vec(<locked>)");
+        drop(guard);
+    }
+
+    #[test]
+    fn test_hash_map_summary_available_and_locked() {
+        use std::collections::HashMap;
+
+        let data = A::new(HashMap::<u32, u32>::with_capacity(16));
+        data.update(|m| {
+            m.insert(1, 1);
+        });
+        assert_eq!(data.len(), 1);
+        assert_eq!(
+            data.summary(),
+            format!("HashMap(len=1, cap={})", data.capacity())
+        );
+
+        let guard = data.write();
+        assert_eq!(data.summary(), "HashMap(<locked>)");
+        drop(guard);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_producing_streams_all_results() {
+        let state = A::new(Vec::new());
+        let (mut results, task) = state.spawn_producing(|state, results| async move {
+            for i in 1..=3 {
+                state.update(|s| s.push(i));
+                results.send(i * 10).await.unwrap();
+            }
+        });
+
+        let mut received = Vec::new();
+        while let Some(result) = results.recv().await {
+            received.push(result);
+        }
+        task.await.unwrap();
+
+        assert_eq!(received, vec![10, 20, 30]);
+        assert_eq!(state.get(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_insert_new_dedupes() {
+        let seen = A::new(std::collections::HashSet::new());
+        assert!(seen.insert_new(42));
+        assert!(!seen.insert_new(42));
+        assert!(seen.insert_new(7));
+        assert_eq!(seen.read().len(), 2);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_subscribe_where_only_emits_matching_writes() {
+        use tokio_stream::StreamExt;
+
+        let level = A::new(0);
+        let high = level.subscribe_where(|v| *v >= 10);
+
+        level.set(3);
+        level.set(12);
+        level.update(|v| *v -= 5);
+        level.set(20);
+        drop(level);
+
+        let seen: Vec<i32> = high.collect().await;
+        assert_eq!(seen, vec![12, 20]);
+    }
+
+    #[test]
+    fn test_lock_state_under_held_guards() {
+        let data = A::new(0);
+        assert_eq!(data.lock_state(), LockState::Free);
+
+        let first = data.read();
+        let second = data.read();
+        assert_eq!(data.lock_state(), LockState::Read);
+        drop((first, second));
+        assert_eq!(data.lock_state(), LockState::Free);
+
+        let writer = data.write();
+        assert_eq!(data.lock_state(), LockState::Write);
+        drop(writer);
+
+        let upgradable = data
+            .try_upgradable_read_for(std::time::Duration::ZERO)
+            .unwrap();
+        assert_eq!(data.lock_state(), LockState::Read);
+        drop(upgradable);
+        assert_eq!(data.lock_state(), LockState::Free);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_phase_group_waits_for_every_worker_between_phases() {
+        let finished_phase_one = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let seen_in_phase_two = A::new(Vec::new());
+        let group = PhaseGroup::new(A::new(0));
+        for delay in [0, 50] {
+            let finished = finished_phase_one.clone();
+            let seen = seen_in_phase_two.clone();
+            group.add_worker(move |counter, phase| {
+                let finished = finished.clone();
+                let seen = seen.clone();
+                async move {
+                    if phase == 0 {
+                        tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                        counter.update(|c| *c += 1);
+                        finished.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    } else {
+                        let done = finished.load(std::sync::atomic::Ordering::SeqCst);
+                        seen.update(|s| s.push(done));
+                        counter.update(|c| *c += 10);
+                    }
+                }
+            });
+        }
+        assert_eq!(group.len(), 2);
+
+        let counter = group.run(2).await.unwrap();
+        assert_eq!(counter.get(), 22);
+        assert_eq!(seen_in_phase_two.get(), vec![2, 2]);
+    }
+
+    #[test]
+    fn test_snapshot_all_is_consistent_across_reversed_orders() {
+        let debit = A::new(100i64);
+        let credit = A::new(0i64);
+        let writers: Vec<_> = (0..2)
+            .map(|i| {
+                let (debit, credit) = (debit.clone(), credit.clone());
+                std::thread::spawn(move || {
+                    for _ in 0..500 {
+                        // Alternate argument order to exercise both lock orders
+                        let (d, c) = if i == 0 {
+                            A::acquire_two(&debit, &credit)
+                        } else {
+                            let (c, d) = A::acquire_two(&credit, &debit);
+                            (d, c)
+                        };
+                        let (mut d, mut c) = (d, c);
+                        *d -= 1;
+                        *c += 1;
+                    }
+                })
+            })
+            .collect();
+
+        for i in 0..500 {
+            let (d, c) = if i % 2 == 0 {
+                snapshot_all!(debit, credit)
+            } else {
+                let (c, d) = snapshot_all!(credit, debit);
+                (d, c)
+            };
+            assert_eq!(d + c, 100);
+        }
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let name = A::new(String::from("total"));
+        assert_eq!(
+            snapshot_all!(name, debit, credit, debit),
+            (String::from("total"), -900, 1000, -900)
+        );
+    }
+
+    #[cfg(feature = "lock_owner_tracking")]
+    #[test]
+    fn test_current_writer_tracks_every_write_path() {
+        let data = A::new(0);
+
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let holder = {
+            let data = data.clone();
+            std::thread::spawn(move || {
+                let _guard = data.try_write_arc().unwrap();
+                locked_tx.send(std::thread::current().id()).unwrap();
+                release_rx.recv().unwrap();
+            })
+        };
+        let holder_id = locked_rx.recv().unwrap();
+        assert_ne!(holder_id, std::thread::current().id());
+        assert_eq!(data.current_writer(), Some(holder_id));
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+
+        // A helper that leaves the value untouched still forgets its owner, so
+        // a later raw write lock does not report it.
+        assert!(!data.set_notify(0));
+        drop(data.write_variant(|value| Some(value)));
+        {
+            let raw = data.clone().into_inner();
+            let _raw = raw.write();
+            assert_eq!(data.current_writer(), None);
+        }
+
+        // An upgrade is not recorded, so it must not report the previous owner.
+        let guard = data
+            .try_upgradable_read_for(std::time::Duration::ZERO)
+            .unwrap();
+        let _writer = parking_lot::ArcRwLockUpgradableReadGuard::upgrade(guard);
+        assert_eq!(data.current_writer(), None);
+    }
+
+    #[cfg(feature = "observers")]
+    #[test]
+    fn test_write_hook_sees_collection_helpers() {
+        use std::collections::{HashMap, VecDeque};
+
+        let sizes = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let log = std::sync::Arc::clone(&sizes);
+        let items =
+            A::new(vec![1, 2, 3]).with_write_hook(move |v: &Vec<i32>| log.lock().push(v.len()));
+        items.reset();
+        assert_eq!(*sizes.lock(), vec![0]);
+
+        let totals = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let log = std::sync::Arc::clone(&totals);
+        let scores = A::new(HashMap::new()).with_write_hook(move |m: &HashMap<&str, i32>| {
+            log.lock().push(m.values().sum::<i32>())
+        });
+        scores.entry_update("alice", || 0, |s| *s += 10);
+        scores.entry_update("bob", || 0, |s| *s += 5);
+        assert_eq!(*totals.lock(), vec![10, 15]);
+
+        let lengths = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let log = std::sync::Arc::clone(&lengths);
+        let queue = A::new(VecDeque::new())
+            .with_write_hook(move |q: &VecDeque<i32>| log.lock().push(q.len()));
+        queue.push_back(1);
+        queue.push_back(2);
+        assert_eq!(queue.pop_front(), Some(1));
+        assert_eq!(*lengths.lock(), vec![1, 2, 1]);
+    }
+
+    #[cfg(feature = "instrumented")]
+    #[tokio::test]
+    async fn test_update_async_counts_contention_once() {
+        let data = A::new(0);
+        let reader = data.read();
+        let writer = {
+            let data = data.clone();
+            tokio::spawn(async move { data.update_async(|x| *x += 1).await })
+        };
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        drop(reader);
+        writer.await.unwrap();
+
+        assert_eq!(data.get(), 1);
+        assert_eq!(data.write_contention(), 1);
+    }
+
+    #[cfg(feature = "labeled")]
+    #[test]
+    fn test_label_added_by_every_closure_helper() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let orders = A::new(vec![1u32]).with_label("orders");
+        let totals = A::new(0u32).with_label("totals");
+        let message = |result: std::thread::Result<()>| {
+            let payload = result.unwrap_err();
+            payload.downcast_ref::<String>().unwrap().clone()
+        };
+
+        let read = message(catch_unwind(AssertUnwindSafe(|| {
+            orders
+                .read_and_then(|_| -> Result<(), ()> { panic!("bad read") })
+                .ok();
+        })));
+        assert_eq!(read, "[orders] bad read");
+
+        let when = message(catch_unwind(AssertUnwindSafe(|| {
+            orders.update_when(|o| &o[0], &1, |_| panic!("bad update"));
+        })));
+        assert_eq!(when, "[orders] bad update");
+
+        let both = message(catch_unwind(AssertUnwindSafe(|| {
+            A::with_both_mut(&orders, &totals, |_, _| panic!("bad pair"));
+        })));
+        assert_eq!(both, "[orders] [totals] bad pair");
+    }
+
+    #[test]
+    fn test_update_batch_discarded_on_panic() {
+        let data = A::new(0u32);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut batch = data.batched();
+            batch.push(|v| *v += 1);
+            panic!("abandon batch");
+        }));
+        assert!(result.is_err());
+        assert_eq!(data.get(), 0);
+    }
 }
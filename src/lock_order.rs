@@ -0,0 +1,75 @@
+//! Runtime enforcement of a lock hierarchy between shared values.
+//!
+//! Handles tagged with [`A::with_level`] record their level as held by the
+//! current thread while one of their guards is alive or a closure-based helper
+//! such as [`A::update`] holds their lock. Acquiring a handle whose level is
+//! lower than one already held on the same thread is a lock-order inversion and
+//! panics in debug builds.
+
+use crate::held::{self, Held};
+use crate::A;
+
+fn check(level: u32) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    if let Some(highest) = held::highest_level().filter(|&highest| highest > level) {
+        panic!(
+            "lock-order inversion: acquiring a level {level} lock while holding a level {highest} lock"
+        );
+    }
+}
+
+impl<T> A<T> {
+    /// Assigns this handle a level in the lock hierarchy.
+    ///
+    /// Locks must be acquired in non-decreasing level order: while a thread holds
+    /// a level `n` lock, through a [`ReadGuard`](crate::ReadGuard) or
+    /// [`WriteGuard`](crate::WriteGuard), a `tspawn!` capture or a closure-based
+    /// helper such as [`A::update`], acquiring a lock with a level below `n`
+    /// panics in debug builds. A guard counts as held by the thread that acquired
+    /// it, while `tspawn!` captures count as held by whichever thread is polling
+    /// their task. Handles without a level are never checked.
+    ///
+    /// The level belongs to the shared value, so every clone of this handle,
+    /// including clones made before this call, sees it. Only available with the
+    /// `lock_order` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let accounts = A::new(vec![100]).with_level(1);
+    /// let audit_log = A::new(Vec::new()).with_level(2);
+    ///
+    /// // Level 1 then level 2 follows the hierarchy.
+    /// accounts.update(|a| audit_log.update(|log| log.push(a[0])));
+    /// assert_eq!(audit_log.get(), vec![100]);
+    /// ```
+    pub fn with_level(self, level: u32) -> Self {
+        *self.extras.level.lock() = Some(level);
+        self
+    }
+
+    /// Returns the lock hierarchy level assigned with [`A::with_level`], if any.
+    pub fn level(&self) -> Option<u32> {
+        *self.extras.level.lock()
+    }
+
+    /// Panics if acquiring this handle now would invert the lock hierarchy.
+    pub(crate) fn check_level(&self) {
+        if let Some(level) = self.level() {
+            check(level);
+        }
+    }
+
+    /// Marks this handle's level held until the result is dropped.
+    ///
+    /// Called once the lock is acquired; the acquisition itself is checked with
+    /// [`A::check_level`] beforehand.
+    pub(crate) fn level_token(&self) -> Option<Held> {
+        self.level()?;
+        self.track()
+    }
+}
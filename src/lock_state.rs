@@ -0,0 +1,49 @@
+//! Point-in-time introspection of an `A<T>`'s lock.
+
+use crate::A;
+
+/// How the lock behind an [`A<T>`] is held at the moment it is inspected.
+///
+/// Returned by [`A::lock_state`]. The state can change as soon as it has been
+/// read, so it is meant for diagnostics and assertions, not for deciding
+/// whether a later lock call will block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LockState {
+    /// Nobody holds the lock.
+    Free,
+    /// One or more readers (or an upgradable reader) hold the lock.
+    Read,
+    /// A writer holds the lock exclusively.
+    Write,
+}
+
+impl<T> A<T> {
+    /// Reports whether the lock is currently free, read-locked or write-locked.
+    ///
+    /// This inspects the lock without acquiring it, so it never blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::{LockState, A};
+    ///
+    /// let data = A::new(0);
+    /// assert_eq!(data.lock_state(), LockState::Free);
+    ///
+    /// let guard = data.read();
+    /// assert_eq!(data.lock_state(), LockState::Read);
+    /// drop(guard);
+    ///
+    /// let guard = data.write();
+    /// assert_eq!(data.lock_state(), LockState::Write);
+    /// ```
+    pub fn lock_state(&self) -> LockState {
+        if self.value.is_locked_exclusive() {
+            LockState::Write
+        } else if self.value.is_locked() {
+            LockState::Read
+        } else {
+            LockState::Free
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Collecting task results in input order.
+
+use tokio::task::{JoinError, JoinHandle};
+
+/// A collection of spawned tasks whose results are returned in index order.
+///
+/// Tasks are registered together with the index of the input they were spawned
+/// for. [`OrderedTasks::join_ordered`] awaits all of them and returns the
+/// results sorted by that index, regardless of the order in which the tasks
+/// completed. This pairs naturally with spawning one `tspawn!` task per element
+/// of indexed shared state.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{A, OrderedTasks, tspawn};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let shards = vec![A::new(10), A::new(20), A::new(30)];
+/// let mut tasks = OrderedTasks::new();
+///
+/// for (index, shard) in shards.iter().cloned().enumerate() {
+///     tasks.spawn(index, tspawn!(ref shard, { *shard * 2 }));
+/// }
+///
+/// assert_eq!(tasks.join_ordered().await?, vec![20, 40, 60]);
+/// # Ok(())
+/// # }
+/// ```
+pub struct OrderedTasks<R> {
+    tasks: Vec<(usize, JoinHandle<R>)>,
+}
+
+impl<R> OrderedTasks<R> {
+    /// Creates an empty collection.
+    pub fn new() -> Self {
+        OrderedTasks { tasks: Vec::new() }
+    }
+
+    /// Registers a spawned task under the given index.
+    pub fn spawn(&mut self, index: usize, handle: JoinHandle<R>) {
+        self.tasks.push((index, handle));
+    }
+
+    /// Returns the number of registered tasks.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if no tasks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Awaits every task and returns the results sorted by index.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`JoinError`] encountered, in index order, if any task
+    /// panicked or was cancelled.
+    pub async fn join_ordered(mut self) -> Result<Vec<R>, JoinError> {
+        self.tasks.sort_by_key(|(index, _)| *index);
+        let mut results = Vec::with_capacity(self.tasks.len());
+        for (_, handle) in self.tasks {
+            results.push(handle.await?);
+        }
+        Ok(results)
+    }
+}
+
+impl<R> Default for OrderedTasks<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
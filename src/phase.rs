@@ -0,0 +1,110 @@
+//! Workers that advance through synchronized phases over one shared value.
+
+use crate::A;
+use parking_lot::Mutex;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::Barrier;
+use tokio::task::{JoinError, JoinSet};
+
+type Worker<T> = Arc<dyn Fn(A<T>, usize) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// A set of workers that run in lock-step phases over the same [`A<T>`].
+///
+/// Every worker runs phase `0`, then waits on a shared
+/// [`tokio::sync::Barrier`] until all workers have finished it, then runs
+/// phase `1`, and so on. No worker starts a phase before every worker has
+/// completed the previous one. Workers receive a clone of the shared handle
+/// and the index of the phase they are running, and should not hold guards
+/// across their own `.await` points.
+///
+/// The group waits on the barrier itself between phases rather than handing
+/// it to the workers, since each phase runs as an owned future that cannot
+/// borrow from the group.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{PhaseGroup, A};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let group = PhaseGroup::new(A::new(Vec::new()));
+/// for worker in 0..2 {
+///     group.add_worker(move |log, phase| async move {
+///         log.update(|log| log.push((phase, worker)));
+///     });
+/// }
+/// let log = group.run(2).await.unwrap();
+///
+/// let phases: Vec<usize> = log.get().iter().map(|(phase, _)| *phase).collect();
+/// assert_eq!(phases, vec![0, 0, 1, 1]);
+/// # }
+/// ```
+pub struct PhaseGroup<T> {
+    data: A<T>,
+    workers: Mutex<Vec<Worker<T>>>,
+}
+
+impl<T> PhaseGroup<T>
+where
+    T: Send + Sync + 'static,
+{
+    /// Creates a group with no workers over `data`.
+    pub fn new(data: A<T>) -> Self {
+        PhaseGroup {
+            data,
+            workers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Adds a worker that is called once per phase by [`run`](Self::run).
+    pub fn add_worker<F, Fut>(&self, f: F)
+    where
+        F: Fn(A<T>, usize) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.workers
+            .lock()
+            .push(Arc::new(move |data, phase| Box::pin(f(data, phase))));
+    }
+
+    /// Returns the number of registered workers.
+    pub fn len(&self) -> usize {
+        self.workers.lock().len()
+    }
+
+    /// Returns `true` if no workers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.workers.lock().is_empty()
+    }
+
+    /// Runs every worker through `phases` phases and returns the shared handle.
+    ///
+    /// Each worker runs as its own tokio task. If any worker panics or is
+    /// cancelled, the remaining workers are aborted, since they could never
+    /// get past the next barrier, and the [`JoinError`] is returned.
+    pub async fn run(self, phases: usize) -> Result<A<T>, JoinError> {
+        let workers = self.workers.into_inner();
+        let barrier = Arc::new(Barrier::new(workers.len()));
+        let mut tasks = JoinSet::new();
+        for worker in workers {
+            let data = self.data.clone();
+            let barrier = barrier.clone();
+            tasks.spawn(async move {
+                for phase in 0..phases {
+                    worker(data.clone(), phase).await;
+                    barrier.wait().await;
+                }
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            if let Err(error) = result {
+                tasks.abort_all();
+                return Err(error);
+            }
+        }
+        Ok(self.data)
+    }
+}
@@ -0,0 +1,66 @@
+//! Sequential async stages over one shared value.
+
+use crate::A;
+use std::future::Future;
+use std::pin::Pin;
+
+type Stage<T> = Box<dyn FnOnce(A<T>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
+/// A chain of async stages that each receive a clone of the same [`A<T>`].
+///
+/// Stages run one after another in the order they were added, each starting
+/// only after the previous one has finished. This structures "mutate, await,
+/// mutate" flows without threading the handle through every step by hand.
+/// Stages should not hold guards across their own `.await` points.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::{Pipeline, A};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let order = A::new(Vec::new());
+/// let order = Pipeline::new(order)
+///     .stage(|o| async move { o.update(|o| o.push("validated")) })
+///     .stage(|o| async move {
+///         tokio::task::yield_now().await;
+///         o.update(|o| o.push("shipped"));
+///     })
+///     .run()
+///     .await;
+/// assert_eq!(order.get(), vec!["validated", "shipped"]);
+/// # }
+/// ```
+pub struct Pipeline<T> {
+    data: A<T>,
+    stages: Vec<Stage<T>>,
+}
+
+impl<T: 'static> Pipeline<T> {
+    /// Creates an empty pipeline over `data`.
+    pub fn new(data: A<T>) -> Self {
+        Pipeline {
+            data,
+            stages: Vec::new(),
+        }
+    }
+
+    /// Appends a stage that runs after all previously added stages.
+    pub fn stage<F, Fut>(mut self, f: F) -> Self
+    where
+        F: FnOnce(A<T>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.stages.push(Box::new(move |data| Box::pin(f(data))));
+        self
+    }
+
+    /// Runs every stage in order and returns the handle they worked on.
+    pub async fn run(self) -> A<T> {
+        for stage in self.stages {
+            stage(self.data.clone()).await;
+        }
+        self.data
+    }
+}
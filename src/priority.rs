@@ -0,0 +1,83 @@
+//! Shared values whose writers take priority over new readers.
+
+use crate::{ReadGuard, WriteGuard, A};
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// An [`A<T>`] whose pending writers block new readers.
+///
+/// Every reader passes through a gate before taking the read lock, and a writer
+/// holds that gate while it waits for the write lock. Once a writer is pending,
+/// no new reader can get in, so the writer only waits for the readers already
+/// inside. This guarantees writer progress under a continuous stream of reads.
+///
+/// Because new readers wait behind a pending writer, a thread must not take a
+/// second read guard while already holding one, or it can deadlock.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::PriorityA;
+///
+/// let config = PriorityA::new(1);
+/// assert_eq!(*config.read(), 1);
+/// *config.write() = 2;
+/// assert_eq!(config.get(), 2);
+/// ```
+pub struct PriorityA<T> {
+    value: A<T>,
+    gate: Arc<Mutex<()>>,
+}
+
+impl<T> Clone for PriorityA<T> {
+    fn clone(&self) -> Self {
+        PriorityA {
+            value: self.value.clone(),
+            gate: Arc::clone(&self.gate),
+        }
+    }
+}
+
+impl<T> PriorityA<T> {
+    /// Creates a new `PriorityA<T>` wrapping the given value.
+    pub fn new(value: T) -> Self {
+        PriorityA {
+            value: A::new(value),
+            gate: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Acquires a read guard, waiting behind any pending writer.
+    pub fn read(&self) -> ReadGuard<T> {
+        drop(self.gate.lock());
+        self.value.read()
+    }
+
+    /// Acquires a write guard, blocking new readers while waiting for it.
+    pub fn write(&self) -> WriteGuard<T> {
+        let _gate = self.gate.lock();
+        self.value.write()
+    }
+
+    /// Returns a clone of the inner value.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.read().clone()
+    }
+
+    /// Updates the inner value using a closure, with writer priority.
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        let _gate = self.gate.lock();
+        self.value.update(f);
+    }
+
+    /// Sets the inner value, with writer priority.
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+}
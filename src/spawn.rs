@@ -0,0 +1,871 @@
+//! Task-spawning helpers for `A<T>`.
+
+use crate::{AbortOnDrop, ReadGuard, A};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, Semaphore};
+use tokio::task::JoinHandle;
+
+type Update<T> = Box<dyn FnOnce(&mut T) + Send>;
+type Query<T> = Box<dyn FnOnce(&T) + Send>;
+
+/// Queues updates to an [`A<T>`] through a bounded channel.
+///
+/// Created by [`A::spawn_bounded`]. Cloning yields another producer for the
+/// same queue; the draining task stops once every updater has been dropped.
+pub struct BoundedUpdater<T> {
+    sender: mpsc::Sender<Update<T>>,
+}
+
+impl<T> Clone for BoundedUpdater<T> {
+    fn clone(&self) -> Self {
+        BoundedUpdater {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T> BoundedUpdater<T> {
+    /// Queues `f` to be applied to the value, waiting while the queue is full.
+    ///
+    /// Returns `false` if the draining task has stopped, in which case `f` is
+    /// dropped without being applied.
+    pub async fn update<F>(&self, f: F) -> bool
+    where
+        F: FnOnce(&mut T) + Send + 'static,
+    {
+        self.sender.send(Box::new(f)).await.is_ok()
+    }
+}
+
+/// Sends updates to the task created by [`A::into_actor`].
+///
+/// Cloning yields another client for the same task; the task stops once every
+/// client has been dropped.
+pub struct WriteClient<T> {
+    sender: mpsc::UnboundedSender<Update<T>>,
+}
+
+impl<T> Clone for WriteClient<T> {
+    fn clone(&self) -> Self {
+        WriteClient {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T> WriteClient<T> {
+    /// Queues `f` to be applied by the actor task, without waiting for it.
+    ///
+    /// Returns `false` if the task has stopped, in which case `f` is dropped
+    /// without being applied.
+    pub fn update<F>(&self, f: F) -> bool
+    where
+        F: FnOnce(&mut T) + Send + 'static,
+    {
+        self.sender.send(Box::new(f)).is_ok()
+    }
+}
+
+/// Spawns a task running `future`, requiring it to be `Send` at the call site.
+///
+/// This is [`tokio::spawn`] with the `Send + 'static` bounds spelled out on
+/// the function itself. [`tspawn!`](macro@crate::tspawn) spawns through it, so a
+/// task body that captures a non-`Send` value such as an `Rc` is reported
+/// against `spawn_send` and the offending capture instead of deep inside
+/// Tokio's internals.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let task = tspawn::spawn_send(async { 6 * 7 });
+/// assert_eq!(task.await?, 42);
+/// # Ok(())
+/// # }
+/// ```
+#[track_caller]
+pub fn spawn_send<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(future)
+}
+
+/// Sends read-only queries to the task created by [`A::query_channel`].
+///
+/// Cloning yields another sender for the same task; the task stops once every
+/// sender has been dropped.
+pub struct QuerySender<T> {
+    sender: mpsc::UnboundedSender<Query<T>>,
+}
+
+impl<T> Clone for QuerySender<T> {
+    fn clone(&self) -> Self {
+        QuerySender {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T> QuerySender<T> {
+    /// Runs `f` against the value inside the owning task and returns its result.
+    ///
+    /// Returns `None` if the task has stopped.
+    pub async fn query<R, F>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&T) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        let query: Query<T> = Box::new(move |value| {
+            // The caller may have stopped waiting, which is fine.
+            let _ = reply.send(f(value));
+        });
+        self.sender.send(query).ok()?;
+        result.await.ok()
+    }
+}
+
+impl<T> A<T> {
+    /// Clones the current value and spawns a task with it.
+    ///
+    /// The value is cloned under a short read lock before spawning, so the task
+    /// works on a snapshot and no lock is held while it runs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = A::new(vec![1, 2, 3]);
+    /// let sum = data.read_then_spawn(|v| async move { v.iter().sum::<i32>() });
+    /// assert_eq!(sum.await?, 6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_then_spawn<R, Fut, F>(&self, f: F) -> JoinHandle<R>
+    where
+        T: Clone,
+        F: FnOnce(T) -> Fut,
+        Fut: Future<Output = R> + Send + 'static,
+        R: Send + 'static,
+    {
+        tokio::spawn(f(self.get()))
+    }
+
+    /// Spawns a task with a clone of this handle that is aborted when the returned
+    /// [`AbortOnDrop`] is dropped.
+    ///
+    /// This ties a background task to the lifetime of a local: once the owner
+    /// goes out of scope the task is cancelled instead of leaking. Call
+    /// [`AbortOnDrop::detach`] to let it run on its own.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let hits = A::new(0);
+    /// let task = hits.spawn_guarded(|hits| async move {
+    ///     hits.update(|h| *h += 1);
+    ///     hits.get()
+    /// });
+    /// assert_eq!(task.await?, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_guarded<R, Fut, F>(&self, f: F) -> AbortOnDrop<R>
+    where
+        F: FnOnce(A<T>) -> Fut,
+        Fut: Future<Output = R> + Send + 'static,
+        R: Send + 'static,
+    {
+        AbortOnDrop::new(tokio::spawn(f(self.clone())))
+    }
+
+    /// Waits for a permit from `semaphore`, then spawns a task with a clone of this
+    /// handle that holds the permit until it finishes.
+    ///
+    /// The permit is owned by the task, so it is released on completion (or
+    /// abort) rather than when this call returns, which correctly bounds the
+    /// number of tasks in flight.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `semaphore` has been closed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use tokio::sync::Semaphore;
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let fetched = A::new(0);
+    /// let limit = Arc::new(Semaphore::new(2));
+    /// let mut tasks = Vec::new();
+    /// for _ in 0..5 {
+    ///     let task = fetched.spawn_permitted(Arc::clone(&limit), |fetched| async move {
+    ///         fetched.update(|n| *n += 1);
+    ///     });
+    ///     tasks.push(task.await);
+    /// }
+    /// for task in tasks {
+    ///     task.await?;
+    /// }
+    /// assert_eq!(fetched.get(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn spawn_permitted<R, Fut, F>(&self, semaphore: Arc<Semaphore>, f: F) -> JoinHandle<R>
+    where
+        F: FnOnce(A<T>) -> Fut,
+        Fut: Future<Output = R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let permit = semaphore
+            .acquire_owned()
+            .await
+            .expect("semaphore passed to spawn_permitted was closed");
+        let future = f(self.clone());
+        tokio::spawn(async move {
+            let _permit = permit;
+            future.await
+        })
+    }
+
+    /// Spawns a task that streams results back over a channel while it works on the
+    /// shared value.
+    ///
+    /// `f` receives a clone of this handle and the sending half of a bounded
+    /// channel holding up to 16 pending results; sends wait while it is full.
+    /// The receiving half is returned together with the task's handle, and
+    /// yields `None` once the task has finished and dropped its sender.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let processed = A::new(0);
+    /// let (mut results, task) = processed.spawn_producing(|processed, results| async move {
+    ///     for page in 1..=3 {
+    ///         processed.update(|n| *n += 1);
+    ///         let _ = results.send(format!("page {page}")).await;
+    ///     }
+    /// });
+    /// while let Some(result) = results.recv().await {
+    ///     println!("{result}");
+    /// }
+    /// task.await.unwrap();
+    /// assert_eq!(processed.get(), 3);
+    /// # }
+    /// ```
+    pub fn spawn_producing<R, Fut, F>(&self, f: F) -> (mpsc::Receiver<R>, JoinHandle<()>)
+    where
+        R: Send + 'static,
+        F: FnOnce(A<T>, mpsc::Sender<R>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(16);
+        (receiver, tokio::spawn(f(self.clone(), sender)))
+    }
+
+    /// Spawns one task per item, each with its own clone of this handle.
+    ///
+    /// `f` is called with a handle clone and the item to build each task's
+    /// future. The handles are returned in item order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let lengths = A::new(0);
+    /// let tasks = lengths.spawn_for_each(["a", "bb", "ccc"], |lengths, word| async move {
+    ///     lengths.update(|n| *n += word.len());
+    /// });
+    /// for task in tasks {
+    ///     task.await?;
+    /// }
+    /// assert_eq!(lengths.get(), 6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_for_each<I, R, Fut, F>(&self, items: I, f: F) -> Vec<JoinHandle<R>>
+    where
+        I: IntoIterator,
+        F: Fn(A<T>, I::Item) -> Fut,
+        Fut: Future<Output = R> + Send + 'static,
+        R: Send + 'static,
+    {
+        items
+            .into_iter()
+            .map(|item| tokio::spawn(f(self.clone(), item)))
+            .collect()
+    }
+
+    /// Spawns a task with a read guard, but only if the read lock is free right now.
+    ///
+    /// The guard is acquired with a non-blocking `try_read` and moved into the
+    /// future built by `f`, so the task reads without cloning and keeps the lock
+    /// until it finishes. Returns `None` without spawning if the value is
+    /// currently locked for writing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = A::new(vec![3, 4]);
+    /// let task = report.spawn_if_readable(|r| async move { r.len() });
+    /// assert_eq!(task.unwrap().await?, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_if_readable<R, Fut, F>(&self, f: F) -> Option<JoinHandle<R>>
+    where
+        F: FnOnce(ReadGuard<T>) -> Fut,
+        Fut: Future<Output = R> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.try_read_arc().map(|guard| tokio::spawn(f(guard)))
+    }
+
+    /// Applies `map` to every handle's value in parallel tasks and reduces the results.
+    ///
+    /// One task is spawned per handle as soon as this is called; each reads its
+    /// value under a read lock. The returned future waits for the tasks and
+    /// combines their results in slice order with `reduce`. It resolves to
+    /// `None` if `handles` is empty.
+    ///
+    /// # Panics
+    ///
+    /// The returned future panics if any `map` call panicked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let shards = [A::new(vec![1, 2]), A::new(vec![3]), A::new(vec![4, 5, 6])];
+    /// let total = A::map_reduce(&shards, |s| s.iter().sum::<i32>(), |a, b| a + b).await;
+    /// assert_eq!(total, Some(21));
+    /// # }
+    /// ```
+    pub fn map_reduce<M, Map, Reduce>(
+        handles: &[A<T>],
+        map: Map,
+        reduce: Reduce,
+    ) -> impl Future<Output = Option<M>>
+    where
+        T: Send + Sync + 'static,
+        M: Send + 'static,
+        Map: Fn(&T) -> M + Send + Sync + Clone + 'static,
+        Reduce: Fn(M, M) -> M,
+    {
+        let tasks: Vec<JoinHandle<M>> = handles
+            .iter()
+            .map(|handle| {
+                let (handle, map) = (handle.clone(), map.clone());
+                tokio::spawn(async move { map(&handle.lock_read()) })
+            })
+            .collect();
+        async move {
+            let mut result = None;
+            for task in tasks {
+                let mapped = match task.await {
+                    Ok(mapped) => mapped,
+                    Err(error) => std::panic::resume_unwind(error.into_panic()),
+                };
+                result = Some(match result {
+                    Some(acc) => reduce(acc, mapped),
+                    None => mapped,
+                });
+            }
+            result
+        }
+    }
+
+    /// Spawns a task that applies `f` to the inner value once per `period`.
+    ///
+    /// The task holds its own clone of the handle and takes the write lock only
+    /// for the duration of each call, as [`A::update`] does. The first call
+    /// happens immediately. The task runs until the returned handle is aborted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let flushes = A::new(0);
+    /// let flusher = flushes.spawn_periodic(Duration::from_millis(10), |n| *n += 1);
+    /// tokio::time::sleep(Duration::from_millis(35)).await;
+    /// flusher.abort();
+    /// assert!(flushes.get() >= 1);
+    /// # }
+    /// ```
+    pub fn spawn_periodic<F>(&self, period: Duration, f: F) -> JoinHandle<()>
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&mut T) + Send + 'static,
+    {
+        let handle = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                handle.update(&f);
+            }
+        })
+    }
+
+    /// Returns a setter that coalesces bursts of writes into the last value.
+    ///
+    /// Each call schedules a [`A::set`] after `delay` and cancels the write
+    /// scheduled by the previous call, if it has not been applied yet. Only the
+    /// last value of a burst with gaps shorter than `delay` is written. The
+    /// setter must be called from within a Tokio runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let query = A::new(String::new());
+    /// let set_query = query.debounced_setter(Duration::from_millis(20));
+    /// for typed in ["r", "ru", "rus", "rust"] {
+    ///     set_query(typed.to_string());
+    /// }
+    /// tokio::time::sleep(Duration::from_millis(50)).await;
+    /// assert_eq!(query.get(), "rust");
+    /// # }
+    /// ```
+    pub fn debounced_setter(&self, delay: Duration) -> impl Fn(T) + Send + Sync + 'static
+    where
+        T: Send + Sync + 'static,
+    {
+        let handle = self.clone();
+        let pending = Arc::new(Mutex::new(None::<JoinHandle<()>>));
+        move |value| {
+            let handle = handle.clone();
+            let task = tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                handle.set(value);
+            });
+            if let Some(previous) = pending.lock().replace(task) {
+                previous.abort();
+            }
+        }
+    }
+
+    /// Spawns a task that calls `f` with every value written through this handle.
+    ///
+    /// The task subscribes to the same change channel as [`A::changes`], so it
+    /// sees writes made through [`A::set`], [`A::update`] and the other mutating
+    /// helpers, in order. It does not keep the value alive: once every handle to
+    /// it has been dropped, the task exits. If `f` falls too far behind, the
+    /// oldest pending values are skipped. Only available with the `stream`
+    /// feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let items = A::new(vec![1, 2]);
+    /// let total = A::new(0);
+    /// let view = total.clone();
+    /// let task = items.spawn_on_change(move |items| view.set(items.iter().sum()));
+    ///
+    /// items.update(|v| v.push(3));
+    /// drop(items);
+    /// task.await.unwrap();
+    /// assert_eq!(total.get(), 6);
+    /// # }
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn spawn_on_change<F>(&self, f: F) -> JoinHandle<()>
+    where
+        T: Clone + Send + 'static,
+        F: Fn(&T) + Send + 'static,
+    {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let mut changes = self.subscribe_changes();
+        tokio::spawn(async move {
+            loop {
+                match changes.recv().await {
+                    Ok(value) => f(&value),
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return,
+                }
+            }
+        })
+    }
+
+    /// Spawns a task that must finish within `deadline`, or the value is reset.
+    ///
+    /// `f` is called with a clone of this handle. If its future has not completed
+    /// when `deadline` elapses, it is dropped at its current `.await` point and
+    /// the shared value is replaced with `T::default()`, so downstream consumers
+    /// see a clean slate instead of a half-finished mutation. Any guards the
+    /// future held are released before the reset.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = A::new(String::new());
+    /// report
+    ///     .spawn_with_deadline(Duration::from_secs(1), |report| async move {
+    ///         report.set(String::from("done"));
+    ///     })
+    ///     .await?;
+    /// assert_eq!(report.get(), "done");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_with_deadline<Fut, F>(&self, deadline: Duration, f: F) -> JoinHandle<()>
+    where
+        T: Default + Send + Sync + 'static,
+        F: FnOnce(A<T>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handle = self.clone();
+        let future = f(self.clone());
+        tokio::spawn(async move {
+            if tokio::time::timeout(deadline, future).await.is_err() {
+                handle.set(T::default());
+            }
+        })
+    }
+
+    /// Spawns a supervised worker that is restarted if it panics.
+    ///
+    /// `f` is called with a clone of this handle to build the worker's future,
+    /// which runs in its own task. If that task panics, the supervisor calls `f`
+    /// again, up to `max_restarts` times. Once the limit is reached, the last
+    /// panic is propagated through the returned handle. The supervisor finishes
+    /// when a worker completes normally.
+    ///
+    /// With the `tracing` feature each restart is logged as a warning; without
+    /// it restarts are silent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let attempts = A::new(0);
+    /// let worker = attempts.spawn_supervised(3, |attempts| async move {
+    ///     attempts.update(|n| *n += 1);
+    /// });
+    /// worker.await?;
+    /// assert_eq!(attempts.get(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_supervised<Fut, F>(&self, max_restarts: usize, f: F) -> JoinHandle<()>
+    where
+        T: Send + Sync + 'static,
+        F: Fn(A<T>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handle = self.clone();
+        tokio::spawn(async move {
+            let mut restarts = 0;
+            loop {
+                let error = match tokio::spawn(f(handle.clone())).await {
+                    Ok(()) => return,
+                    Err(error) if error.is_panic() => error,
+                    // The runtime is shutting down.
+                    Err(_) => return,
+                };
+                if restarts == max_restarts {
+                    std::panic::resume_unwind(error.into_panic());
+                }
+                restarts += 1;
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    restarts,
+                    max_restarts,
+                    "tspawn supervised task panicked, restarting"
+                );
+            }
+        })
+    }
+
+    /// Spawns a task that flushes the value once writes have settled, returning
+    /// the task and a `mark_dirty` closure.
+    ///
+    /// Call `mark_dirty` after each write. The task calls `flush` under a read lock
+    /// once `idle` has passed without another `mark_dirty` call, so a burst of
+    /// writes results in a single flush. When every copy of `mark_dirty` has been
+    /// dropped, the task flushes any pending changes and exits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let buffer = A::new(Vec::new());
+    /// let (task, mark_dirty) = buffer.spawn_flusher(Duration::from_millis(20), |lines| {
+    ///     println!("persisting {} lines", lines.len());
+    /// });
+    /// for line in ["a", "b", "c"] {
+    ///     buffer.update(|b| b.push(line));
+    ///     mark_dirty();
+    /// }
+    /// drop(mark_dirty);
+    /// task.await.unwrap();
+    /// # }
+    /// ```
+    pub fn spawn_flusher<F>(
+        &self,
+        idle: Duration,
+        flush: F,
+    ) -> (JoinHandle<()>, impl Fn() + Send + Sync + 'static)
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&T) + Send + 'static,
+    {
+        let (dirty, mut marks) = mpsc::unbounded_channel::<()>();
+        let handle = self.clone();
+        let task = tokio::spawn(async move {
+            while marks.recv().await.is_some() {
+                // Restart the idle window on every mark until the writes settle.
+                loop {
+                    match tokio::time::timeout(idle, marks.recv()).await {
+                        Ok(Some(())) => continue,
+                        Ok(None) => {
+                            flush(&handle.lock_read());
+                            return;
+                        }
+                        Err(_) => break,
+                    }
+                }
+                flush(&handle.lock_read());
+            }
+        });
+        let mark_dirty = move || {
+            // The task only stops once every sender is gone, so this cannot fail.
+            let _ = dirty.send(());
+        };
+        (task, mark_dirty)
+    }
+
+    /// Spawns a task that applies queued updates, returning the producer side.
+    ///
+    /// At most `capacity` updates wait in the queue; once it is full,
+    /// [`BoundedUpdater::update`] waits until the task has applied one. This gives
+    /// a write-heavy producer natural backpressure instead of piling up work.
+    /// Updates are applied in order, each under its own write lock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let total = A::new(0);
+    /// let (updater, task) = total.spawn_bounded(4);
+    /// for n in 1..=10 {
+    ///     updater.update(move |t| *t += n).await;
+    /// }
+    /// drop(updater);
+    /// task.await?;
+    /// assert_eq!(total.get(), 55);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_bounded(&self, capacity: usize) -> (BoundedUpdater<T>, JoinHandle<()>)
+    where
+        T: Send + Sync + 'static,
+    {
+        let (sender, mut receiver) = mpsc::channel::<Update<T>>(capacity);
+        let handle = self.clone();
+        let task = tokio::spawn(async move {
+            while let Some(f) = receiver.recv().await {
+                handle.update(f);
+            }
+        });
+        (BoundedUpdater { sender }, task)
+    }
+
+    /// Moves the handle into a task that applies all updates serially.
+    ///
+    /// Writers send mutations through the returned [`WriteClient`] instead of
+    /// taking the lock themselves, so many contending writers become a single
+    /// one. Other clones of the handle can still read, or write directly. The
+    /// task finishes after every client has been dropped and the queued updates
+    /// have been applied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let hits = A::new(0);
+    /// let (client, task) = hits.clone().into_actor();
+    /// client.update(|n| *n += 1);
+    /// drop(client);
+    /// task.await?;
+    /// assert_eq!(hits.get(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_actor(self) -> (WriteClient<T>, JoinHandle<()>)
+    where
+        T: Send + Sync + 'static,
+    {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Update<T>>();
+        let task = tokio::spawn(async move {
+            while let Some(f) = receiver.recv().await {
+                self.update(f);
+            }
+        });
+        (WriteClient { sender }, task)
+    }
+
+    /// Moves the handle into a task that answers read-only queries.
+    ///
+    /// Callers send closures through the returned [`QuerySender`]; the task runs
+    /// each one under a read lock and replies with its result. This gives other
+    /// components read access to state owned by an actor without handing them
+    /// the lock. The task finishes after every sender has been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let inventory = A::new(vec![("apples", 3), ("pears", 5)]);
+    /// let (queries, task) = inventory.query_channel();
+    ///
+    /// let total = queries.query(|items| items.iter().map(|(_, n)| n).sum::<i32>()).await;
+    /// assert_eq!(total, Some(8));
+    /// drop(queries);
+    /// task.await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_channel(self) -> (QuerySender<T>, JoinHandle<()>)
+    where
+        T: Send + Sync + 'static,
+    {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Query<T>>();
+        let task = tokio::spawn(async move {
+            while let Some(query) = receiver.recv().await {
+                query(&self.lock_read());
+            }
+        });
+        (QuerySender { sender }, task)
+    }
+}
+
+impl<T> A<VecDeque<T>> {
+    /// Spawns a worker that pops items from the front of the queue and passes them
+    /// to `handler`.
+    ///
+    /// The queue lock is released before `handler` runs, so producers are never
+    /// blocked by slow handlers. The worker yields to the runtime after each item.
+    /// While the queue is empty it sleeps on a tokio timer between checks,
+    /// doubling the delay up to 10ms, so an idle worker does not spin and picks
+    /// up items pushed by any write path. It runs until the returned handle is
+    /// aborted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::VecDeque;
+    /// use tspawn::A;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let jobs = A::new(VecDeque::from([1, 2, 3]));
+    /// let done = A::new(Vec::new());
+    /// let worker = {
+    ///     let done = done.clone();
+    ///     jobs.spawn_consumer(move |job| done.update(|d| d.push(job)))
+    /// };
+    /// while done.read().len() < 3 {
+    ///     tokio::task::yield_now().await;
+    /// }
+    /// worker.abort();
+    /// assert_eq!(done.get(), vec![1, 2, 3]);
+    /// # }
+    /// ```
+    pub fn spawn_consumer<F>(&self, handler: F) -> JoinHandle<()>
+    where
+        T: Send + Sync + 'static,
+        F: Fn(T) + Send + 'static,
+    {
+        const MIN_IDLE: Duration = Duration::from_micros(50);
+        const MAX_IDLE: Duration = Duration::from_millis(10);
+
+        let queue = self.clone();
+        tokio::spawn(async move {
+            let mut idle = MIN_IDLE;
+            loop {
+                match queue.pop_front() {
+                    Some(item) => {
+                        handler(item);
+                        idle = MIN_IDLE;
+                        tokio::task::yield_now().await;
+                    }
+                    None => {
+                        tokio::time::sleep(idle).await;
+                        idle = (idle * 2).min(MAX_IDLE);
+                    }
+                }
+            }
+        })
+    }
+}
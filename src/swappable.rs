@@ -0,0 +1,154 @@
+//! Shared values backed by an atomically swappable pointer.
+
+use arc_swap::ArcSwap;
+use parking_lot::{ArcRwLockReadGuard, ArcRwLockWriteGuard, RawRwLock, RwLock};
+use std::sync::Arc;
+
+/// A shared value whose entire backing `RwLock` can be hot-swapped.
+///
+/// Clones of an [`A<T>`](crate::A) each hold their own `Arc` to the lock, so
+/// replacing the lock in one handle cannot affect the others. `SwappableA<T>`
+/// adds one level of indirection through an [`ArcSwap`]: every clone shares the
+/// slot, and [`SwappableA::swap_backing`] atomically points all of them at a
+/// freshly built lock. Guards taken before the swap keep the old lock alive
+/// and keep working on it. Only available with the `arc_swap` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use parking_lot::RwLock;
+/// use std::sync::Arc;
+/// use tspawn::SwappableA;
+///
+/// let config = SwappableA::new(String::from("v1"));
+/// let worker_view = config.clone();
+///
+/// config.swap_backing(Arc::new(RwLock::new(String::from("v2"))));
+/// assert_eq!(worker_view.get(), "v2");
+/// ```
+pub struct SwappableA<T> {
+    backing: Arc<ArcSwap<RwLock<T>>>,
+}
+
+impl<T> Clone for SwappableA<T> {
+    fn clone(&self) -> Self {
+        SwappableA {
+            backing: Arc::clone(&self.backing),
+        }
+    }
+}
+
+impl<T> SwappableA<T> {
+    /// Creates a new `SwappableA<T>` wrapping the given value.
+    pub fn new(value: T) -> Self {
+        Self::from_arc(Arc::new(RwLock::new(value)))
+    }
+
+    /// Creates a new `SwappableA<T>` backed by an existing lock.
+    pub fn from_arc(backing: Arc<RwLock<T>>) -> Self {
+        SwappableA {
+            backing: Arc::new(ArcSwap::new(backing)),
+        }
+    }
+
+    /// Acquires a read guard on the current backing lock.
+    pub fn read(&self) -> ArcRwLockReadGuard<RawRwLock, T> {
+        self.backing.load_full().read_arc()
+    }
+
+    /// Acquires a write guard on the current backing lock.
+    pub fn write(&self) -> ArcRwLockWriteGuard<RawRwLock, T> {
+        self.backing.load_full().write_arc()
+    }
+
+    /// Returns a clone of the inner value.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.read().clone()
+    }
+
+    /// Sets the inner value in the current backing lock.
+    pub fn set(&self, value: T) {
+        *self.write() = value;
+    }
+
+    /// Updates the inner value in the current backing lock using a closure.
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        f(&mut self.write());
+    }
+
+    /// Returns the current backing lock.
+    pub fn backing(&self) -> Arc<RwLock<T>> {
+        self.backing.load_full()
+    }
+
+    /// Atomically replaces the backing lock for all clones, returning the old one.
+    pub fn swap_backing(&self, new: Arc<RwLock<T>>) -> Arc<RwLock<T>> {
+        self.backing.swap(new)
+    }
+}
+
+/// A lock-free shared snapshot, for read-mostly values replaced as a whole.
+///
+/// Where [`A<T>`](crate::A) guards the value with an `RwLock`, `ArcSwapA<T>`
+/// stores an `Arc<T>` in an [`ArcSwap`]: readers load the current snapshot
+/// without locking, and writers publish a new one atomically. Clones share the
+/// same slot. Only available with the `arc_swap` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use tspawn::ArcSwapA;
+///
+/// let config = ArcSwapA::new(vec!["eu-west"]);
+/// let seen = config.load();
+/// let previous = config.compare_and_swap(&seen, Arc::new(vec!["eu-west", "us-east"]));
+/// assert!(Arc::ptr_eq(&previous, &seen));
+/// assert_eq!(config.load().len(), 2);
+/// ```
+pub struct ArcSwapA<T> {
+    current: Arc<ArcSwap<T>>,
+}
+
+impl<T> Clone for ArcSwapA<T> {
+    fn clone(&self) -> Self {
+        ArcSwapA {
+            current: Arc::clone(&self.current),
+        }
+    }
+}
+
+impl<T> ArcSwapA<T> {
+    /// Creates a new `ArcSwapA<T>` holding the given value.
+    pub fn new(value: T) -> Self {
+        ArcSwapA {
+            current: Arc::new(ArcSwap::from_pointee(value)),
+        }
+    }
+
+    /// Returns the current snapshot.
+    pub fn load(&self) -> Arc<T> {
+        self.current.load_full()
+    }
+
+    /// Publishes a new snapshot.
+    pub fn store(&self, new: Arc<T>) {
+        self.current.store(new);
+    }
+
+    /// Publishes `new` only if the current snapshot is still `current`.
+    ///
+    /// Snapshots are compared by pointer, not by value. Returns the snapshot
+    /// that was stored before the call: if it is the same `Arc` as `current`,
+    /// the swap succeeded; otherwise nothing was changed and the caller can
+    /// retry against the returned snapshot.
+    pub fn compare_and_swap(&self, current: &Arc<T>, new: Arc<T>) -> Arc<T> {
+        arc_swap::Guard::into_inner(self.current.compare_and_swap(current, new))
+    }
+}
@@ -0,0 +1,13 @@
+//! The `async` block wrapped around every `tspawn!` task body.
+//!
+//! Compiler diagnostics about the task future (for example a captured value
+//! that is not `Send`) point at this block. It lives in its own file so those
+//! spans stay put when the macros in `lib.rs` change.
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tspawn_async {
+    ($body:block) => {
+        async move $body
+    };
+}
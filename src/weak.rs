@@ -0,0 +1,138 @@
+//! Weak handles and self-pruning observer registries.
+
+use crate::a::Extras;
+use crate::A;
+use parking_lot::RwLock;
+use std::sync::{Arc, Weak};
+
+/// A weak reference to the value shared by an [`A<T>`].
+///
+/// A `WeakA` does not keep the value alive. It is created with [`A::downgrade`]
+/// and turned back into a handle with [`WeakA::upgrade`], which fails once every
+/// `A<T>` clone has been dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use tspawn::A;
+///
+/// let data = A::new(42);
+/// let weak = data.downgrade();
+/// assert_eq!(weak.upgrade().map(|d| d.get()), Some(42));
+///
+/// drop(data);
+/// assert!(weak.upgrade().is_none());
+/// ```
+pub struct WeakA<T> {
+    value: Weak<RwLock<T>>,
+    extras: Extras<T>,
+}
+
+impl<T> Clone for WeakA<T> {
+    fn clone(&self) -> Self {
+        WeakA {
+            value: Weak::clone(&self.value),
+            extras: self.extras.clone(),
+        }
+    }
+}
+
+impl<T> WeakA<T> {
+    /// Attempts to obtain a strong handle, returning `None` if the value was dropped.
+    pub fn upgrade(&self) -> Option<A<T>> {
+        self.value.upgrade().map(|value| A {
+            value,
+            extras: self.extras.clone(),
+        })
+    }
+
+    /// Returns `true` if the shared value is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.value.strong_count() > 0
+    }
+}
+
+impl<T> A<T> {
+    /// Creates a [`WeakA`] pointing at the same shared value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let data = A::new(1);
+    /// let weak = data.downgrade();
+    /// weak.upgrade().unwrap().set(2);
+    /// assert_eq!(data.get(), 2);
+    /// ```
+    pub fn downgrade(&self) -> WeakA<T> {
+        WeakA {
+            value: Arc::downgrade(&self.value),
+            extras: self.extras.clone(),
+        }
+    }
+
+    /// Registers a weak reference to this handle in a shared registry.
+    ///
+    /// The registry does not keep the subscriber alive; once every clone of this
+    /// handle is dropped, the entry is pruned by the next [`broadcast`] call.
+    ///
+    /// [`broadcast`]: A::broadcast
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let registry = A::new(Vec::new());
+    /// let subscriber = A::new(0);
+    /// subscriber.subscribe_weak(&registry);
+    ///
+    /// registry.broadcast(|count| *count += 1);
+    /// assert_eq!(subscriber.get(), 1);
+    /// ```
+    pub fn subscribe_weak(&self, registry: &A<Vec<WeakA<T>>>) {
+        let mut guard = registry.lock_write();
+        guard.push(self.downgrade());
+        registry.finish_write(guard);
+    }
+}
+
+impl<T> A<Vec<WeakA<T>>> {
+    /// Applies `f` to every live subscriber and prunes dead ones in a single pass.
+    ///
+    /// The registry is write-locked for the duration of the pass, and each live
+    /// subscriber is updated under its own write lock. Returns the number of live
+    /// subscribers that were updated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tspawn::A;
+    ///
+    /// let registry = A::new(Vec::new());
+    /// let kept = A::new(0);
+    /// kept.subscribe_weak(&registry);
+    /// A::new(0).subscribe_weak(&registry); // Dropped immediately
+    ///
+    /// assert_eq!(registry.broadcast(|count| *count += 1), 1);
+    /// assert_eq!(registry.read().len(), 1);
+    /// ```
+    pub fn broadcast<F>(&self, mut f: F) -> usize
+    where
+        F: FnMut(&mut T),
+    {
+        let mut delivered = 0;
+        let mut guard = self.lock_write();
+        guard.retain(|subscriber| match subscriber.upgrade() {
+            Some(handle) => {
+                handle.update(&mut f);
+                delivered += 1;
+                true
+            }
+            None => false,
+        });
+        self.finish_write(guard);
+        delivered
+    }
+}
@@ -0,0 +1,11 @@
+use std::rc::Rc;
+use tspawn::{tspawn, A};
+
+#[tokio::main]
+async fn main() {
+    let data = A::new(0);
+    let local = Rc::new(1);
+    tspawn!(mut data, {
+        *data += *local;
+    });
+}
@@ -0,0 +1,105 @@
+//! # tspawn-derive
+//!
+//! Derive macros for [`tspawn`](https://docs.rs/tspawn). Enable them through the
+//! `derive` feature of `tspawn` rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+/// Generates a companion struct whose fields are each wrapped in `tspawn::A`.
+///
+/// For a struct `State`, `#[derive(Shared)]` generates `SharedState` with:
+///
+/// - one `A<FieldTy>` field per field of `State`, each independently locked
+/// - an accessor `fn field(&self) -> &A<FieldTy>` per field
+/// - a constructor `SharedState::new(field, ...)` taking the plain field values
+/// - `Clone`, which clones every handle (sharing the underlying values)
+/// - `From<State>` and `State::into_shared(self)`
+///
+/// Only structs with named fields are supported.
+#[proc_macro_derive(Shared)]
+pub fn derive_shared(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_shared(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_shared(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "#[derive(Shared)] requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[derive(Shared)] can only be used on structs",
+            ))
+        }
+    };
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let shared = format_ident!("Shared{}", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let names: Vec<_> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let field_vis: Vec<_> = fields.iter().map(|f| &f.vis).collect();
+    let doc = format!(
+        "Independently locked fields of [`{}`], generated by `#[derive(Shared)]`.",
+        name
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #shared #impl_generics #where_clause {
+            #(#names: ::tspawn::A<#types>,)*
+        }
+
+        impl #impl_generics #shared #ty_generics #where_clause {
+            /// Wraps each field value in its own `A`.
+            #[allow(clippy::too_many_arguments)]
+            #vis fn new(#(#names: #types),*) -> Self {
+                Self {
+                    #(#names: ::tspawn::A::new(#names),)*
+                }
+            }
+
+            #(
+                /// Returns the shared handle for this field.
+                #field_vis fn #names(&self) -> &::tspawn::A<#types> {
+                    &self.#names
+                }
+            )*
+        }
+
+        impl #impl_generics ::core::clone::Clone for #shared #ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                Self {
+                    #(#names: ::core::clone::Clone::clone(&self.#names),)*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for #shared #ty_generics #where_clause {
+            fn from(value: #name #ty_generics) -> Self {
+                Self::new(#(value.#names),*)
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Converts this value into its independently locked companion struct.
+            #vis fn into_shared(self) -> #shared #ty_generics {
+                ::core::convert::From::from(self)
+            }
+        }
+    })
+}